@@ -0,0 +1,268 @@
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed universe of `len` bits packed into `u64` words
+#[derive(Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(test, derive(Debug))]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; words_for(len)],
+            len,
+        }
+    }
+
+    pub fn full(len: usize) -> Self {
+        let mut set = Self {
+            words: vec![u64::MAX; words_for(len)],
+            len,
+        };
+        set.clear_unused();
+
+        set
+    }
+
+    /// Size of the universe, not the number of set bits
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.len && self.words[index / WORD_BITS] & mask(index) != 0
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        assert!(index < self.len, "bit {} out of range {}", index, self.len);
+        self.words[index / WORD_BITS] |= mask(index);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        assert!(index < self.len, "bit {} out of range {}", index, self.len);
+        self.words[index / WORD_BITS] &= !mask(index);
+    }
+
+    /// Grow the universe by one bit with the given value
+    pub fn push(&mut self, value: bool) {
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.push(0);
+        }
+
+        self.len += 1;
+
+        if value {
+            self.insert(self.len - 1);
+        }
+    }
+
+    /// Shrink the universe by removing one bit, moving the bits above it down by one
+    pub fn shift_out(&mut self, index: usize) {
+        assert!(index < self.len, "bit {} out of range {}", index, self.len);
+
+        let first = index / WORD_BITS;
+        let below = mask(index) - 1;
+        let word = self.words[first];
+        self.words[first] = (word & below) | ((word >> 1) & !below);
+
+        for i in first + 1..self.words.len() {
+            self.words[i - 1] |= (self.words[i] & 1) << (WORD_BITS - 1);
+            self.words[i] >>= 1;
+        }
+
+        self.len -= 1;
+        self.words.truncate(words_for(self.len));
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(word, other)| word & !other == 0)
+    }
+
+    /// Whether both sets contain the same elements below `index`
+    pub fn equal_below(&self, other: &Self, index: usize) -> bool {
+        let full_words = index / WORD_BITS;
+
+        if self.words[..full_words] != other.words[..full_words] {
+            return false;
+        }
+
+        let rest = index % WORD_BITS;
+
+        rest == 0 || (self.words[full_words] ^ other.words[full_words]) & ((1 << rest) - 1) == 0
+    }
+
+    /// Iterate over the indices of all set bits in ascending order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+
+                Some(i * WORD_BITS + bit)
+            })
+        })
+    }
+
+    fn clear_unused(&mut self) {
+        let used = self.len % WORD_BITS;
+
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+}
+
+fn words_for(len: usize) -> usize {
+    len.div_ceil(WORD_BITS)
+}
+
+fn mask(index: usize) -> u64 {
+    1 << (index % WORD_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitSet;
+
+    #[test]
+    fn full() {
+        let set = BitSet::full(70);
+
+        assert!(set.contains(69));
+        assert!(!set.contains(70));
+        assert_eq!(set.ones().count(), 70);
+        assert_eq!(set, {
+            let mut manual = BitSet::new(70);
+            (0..70).for_each(|i| manual.insert(i));
+            manual
+        });
+    }
+
+    #[test]
+    fn insert() {
+        let mut set = BitSet::new(130);
+
+        set.insert(0);
+        set.insert(129);
+
+        assert!(set.contains(0));
+        assert!(!set.contains(64));
+        assert!(set.contains(129));
+        assert_eq!(set.ones().collect::<Vec<_>>(), vec![0, 129]);
+
+        set.remove(0);
+
+        assert_eq!(set.ones().collect::<Vec<_>>(), vec![129]);
+    }
+
+    #[test]
+    fn push() {
+        let mut set = BitSet::new(63);
+
+        set.push(true);
+        set.push(false);
+        set.push(true);
+
+        assert!(!set.contains(66));
+        assert_eq!(set.ones().collect::<Vec<_>>(), vec![63, 65]);
+    }
+
+    #[test]
+    fn shift_out() {
+        let mut set = BitSet::new(130);
+
+        set.insert(1);
+        set.insert(64);
+        set.insert(129);
+
+        set.shift_out(1);
+
+        assert_eq!(set.len(), 129);
+        assert_eq!(set.ones().collect::<Vec<_>>(), vec![63, 128]);
+
+        set.shift_out(0);
+        set.shift_out(0);
+
+        assert_eq!(set.len(), 127);
+        assert_eq!(set.ones().collect::<Vec<_>>(), vec![61, 126]);
+        assert_eq!(set, {
+            let mut manual = BitSet::new(127);
+            manual.insert(61);
+            manual.insert(126);
+            manual
+        });
+    }
+
+    #[test]
+    fn intersect_with() {
+        let mut a = BitSet::new(100);
+        let mut b = BitSet::new(100);
+
+        a.insert(1);
+        a.insert(70);
+        a.insert(99);
+        b.insert(70);
+        b.insert(99);
+        b.insert(2);
+
+        a.intersect_with(&b);
+
+        assert_eq!(a.ones().collect::<Vec<_>>(), vec![70, 99]);
+    }
+
+    #[test]
+    fn subset() {
+        let mut a = BitSet::new(100);
+        let mut b = BitSet::new(100);
+
+        a.insert(70);
+        b.insert(70);
+        b.insert(3);
+
+        assert!(a.is_subset(&b));
+        assert!(!b.is_subset(&a));
+
+        a.insert(3);
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn equal_below() {
+        let mut a = BitSet::new(100);
+        let mut b = BitSet::new(100);
+
+        a.insert(2);
+        b.insert(2);
+        b.insert(66);
+
+        assert!(a.equal_below(&b, 0));
+        assert!(a.equal_below(&b, 64));
+        assert!(a.equal_below(&b, 66));
+        assert!(!a.equal_below(&b, 67));
+        assert!(!a.equal_below(&b, 100));
+    }
+}
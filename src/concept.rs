@@ -1,7 +1,10 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
 #[derive(Eq, PartialEq)]
 #[cfg_attr(test, derive(Debug))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Concept {
     pub extents: Vec<String>,
     pub intents: Vec<String>,
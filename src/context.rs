@@ -1,19 +1,49 @@
-use anyhow::Result;
-use csv::Reader;
-use std::{borrow::Cow, fmt};
+use anyhow::{anyhow, bail, Result};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::{borrow::Cow, collections::HashMap, fmt, fs::File, path::Path};
 
-use ndarray::{iter::Lanes, Array, Array2, ArrayView, Axis, Dim};
+pub use self::{arrows::Arrow, cex::CexSections, csv::CsvOptions, reduce::Reduction};
 
 use crate::{
-    implications::{preclosure_operator, Implication},
-    next_closure, Concept,
+    bitset::BitSet,
+    implications::{preclosure_operator, Conclusion, Implication, IndexImplication},
+    names::Names,
+    next_closure_set, Concept, Lattice,
 };
 
+mod arrows;
+mod cex;
+mod csv;
+mod cxt;
+mod reduce;
+#[cfg(feature = "serde")]
+mod serialize;
+
+/// Algorithm used to enumerate the concepts of a [Context]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Algorithm {
+    /// Ganter's NextClosure, yielding concepts in lectic order of their intents
+    #[default]
+    NextClosure,
+    /// Kuznetsov's Close-by-One, generating concepts depth-first by adding one attribute at a
+    /// time to an intent and keeping the closure only when it adds no smaller attribute, the
+    /// canonicity test
+    CloseByOne,
+    /// Close-by-One with the subtrees of each concept explored in parallel using rayon,
+    /// generating concepts in the same order as [Algorithm::CloseByOne].
+    /// Runs sequentially unless the `parallel` feature is enabled.
+    ParallelCloseByOne,
+}
+
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Context {
-    array: Array2<bool>,
-    objects: Vec<String>,
-    attributes: Vec<String>,
+    /// Incidence of each object over the attributes
+    rows: Vec<BitSet>,
+    /// Incidence of each attribute over the objects
+    columns: Vec<BitSet>,
+    objects: Names,
+    attributes: Names,
 }
 
 impl Context {
@@ -21,170 +51,337 @@ impl Context {
         Self {
             objects: (1..=objects).map(|i| i.to_string()).collect(),
             attributes: (1..=attributes).map(|i| i.to_string()).collect(),
-            array: Array::default((objects, attributes)),
+            rows: vec![BitSet::new(attributes); objects],
+            columns: vec![BitSet::new(objects); attributes],
         }
     }
 
-    pub fn objects(&self) -> Cow<[String]> {
-        Cow::Borrowed(&self.objects)
+    /// Context without any objects over the given attributes
+    pub(crate) fn with_attributes(attributes: Names) -> Self {
+        Self {
+            rows: Vec::new(),
+            columns: vec![BitSet::new(0); attributes.len()],
+            objects: Names::default(),
+            attributes,
+        }
     }
 
-    pub fn attributes(&self) -> Cow<[String]> {
-        Cow::Borrowed(&self.attributes)
+    /// Read a context from a file, picking the format from its extension: `.cxt` for
+    /// Burmeister, `.cex` for ConExp and CSV otherwise
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let reader = File::open(path)?;
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("cxt") => Self::from_cxt_reader(reader),
+            Some("cex") => Self::from_cex_reader(reader),
+            _ => Self::from_csv_reader(reader),
+        }
     }
 
-    pub fn from_csv(data: &str) -> Result<Self> {
-        let mut reader = Reader::from_reader(data.as_bytes());
-
-        let mut headers = reader.headers()?.iter();
-        // First column is for objects
-        headers.next();
-        let headers: Vec<String> = headers.map(|h| h.trim().to_string()).collect();
-        let num_attributes = headers.len();
+    pub fn objects(&self) -> Cow<'_, [String]> {
+        Cow::Borrowed(self.objects.as_slice())
+    }
 
-        let mut objects: Vec<String> = Vec::new();
-        let mut array = Array::default((0, num_attributes));
+    pub fn attributes(&self) -> Cow<'_, [String]> {
+        Cow::Borrowed(self.attributes.as_slice())
+    }
 
-        for record in reader.into_records() {
-            let record = record?;
+    pub fn intents(&self, objects: &[String]) -> Option<Vec<String>> {
+        let objects = self.objects.set(objects)?;
 
-            let mut iter = record.iter();
-            objects.push(
-                iter.next()
-                    .map_or_else(|| (objects.len() + 1).to_string(), |v| v.trim().to_string()),
-            );
-            let result = iter
-                .map(|v| v.trim().to_uppercase() == "X")
-                .collect::<Vec<_>>();
+        Some(self.attributes.names(&self.intents_set(&objects)))
+    }
 
-            array.append(
-                Axis(0),
-                ArrayView::from_shape((1, num_attributes), &result)?,
-            )?;
-        }
+    pub fn extents(&self, attributes: &[String]) -> Option<Vec<String>> {
+        let attributes = self.attributes.set(attributes)?;
 
-        Ok(Self {
-            objects,
-            attributes: headers,
-            array,
-        })
+        Some(self.objects.names(&self.extents_set(&attributes)))
     }
 
-    pub fn intents(&self, objects: &[String]) -> Option<Vec<String>> {
-        Self::der(&self.objects, objects, self.array.rows(), &self.attributes)
+    pub fn closure_intents(&self, objects: &[String]) -> Option<Vec<String>> {
+        let objects = self.objects.set(objects)?;
+
+        Some(self.objects.names(&self.closure_intents_set(&objects)))
     }
 
-    pub fn extents(&self, attributes: &[String]) -> Option<Vec<String>> {
-        Self::der(
-            &self.attributes,
-            attributes,
-            self.array.columns(),
-            &self.objects,
+    pub fn closure_extents(&self, attributes: &[String]) -> Option<Vec<String>> {
+        let attributes = self.attributes.set(attributes)?;
+
+        Some(
+            self.attributes
+                .names(&self.closure_extents_set(&attributes)),
         )
     }
 
-    pub fn closure_intents(&self, objects: &[String]) -> Option<Vec<String>> {
-        let attributes = self.intents(objects)?;
-        self.extents(&attributes)
+    pub(crate) fn intents_set(&self, objects: &BitSet) -> BitSet {
+        Self::der(objects, &self.rows, self.attributes.len())
     }
 
-    pub fn closure_extents(&self, attributes: &[String]) -> Option<Vec<String>> {
-        let objects = self.extents(attributes)?;
-        self.intents(&objects)
+    pub(crate) fn extents_set(&self, attributes: &BitSet) -> BitSet {
+        Self::der(attributes, &self.columns, self.objects.len())
+    }
+
+    pub(crate) fn closure_intents_set(&self, objects: &BitSet) -> BitSet {
+        self.extents_set(&self.intents_set(objects))
+    }
+
+    pub(crate) fn closure_extents_set(&self, attributes: &BitSet) -> BitSet {
+        self.intents_set(&self.extents_set(attributes))
     }
 
-    fn der(
-        inputs_named: &[String],
-        inputs: &[String],
-        set: Lanes<bool, Dim<[usize; 1]>>,
-        outputs_named: &[String],
-    ) -> Option<Vec<String>> {
+    fn der(inputs: &BitSet, lanes: &[BitSet], outputs: usize) -> BitSet {
         // Empty set is always all the attributes / objects
-        if inputs.is_empty() {
-            return Some(outputs_named.to_vec());
+        let mut der = BitSet::full(outputs);
+
+        for i in inputs.ones() {
+            der.intersect_with(&lanes[i]);
         }
 
-        let indices: Vec<_> = inputs_named
-            .iter()
-            .enumerate()
-            .filter_map(|(i, o)| if inputs.contains(o) { Some(i) } else { None })
-            .collect();
+        der
+    }
 
-        let der = set
-            .into_iter()
+    pub fn object_has_attribute(&self, object: &str, attribute: &str) -> Option<bool> {
+        let object_index = self.objects.index(object)?;
+        let attribute_index = self.attributes.index(attribute)?;
+
+        Some(self.rows[object_index].contains(attribute_index))
+    }
+
+    /// Whether every object having all premise attributes also has all conclusion attributes
+    pub fn respects(&self, implication: &Implication) -> bool {
+        self.counterexamples(implication).is_empty()
+    }
+
+    /// Objects having all premise attributes but lacking some conclusion attribute.
+    /// Unknown attributes are held by no object.
+    pub fn counterexamples(&self, implication: &Implication) -> Vec<String> {
+        let has_all = |row: &BitSet, attributes: &[String]| {
+            attributes
+                .iter()
+                .all(|a| self.attributes.index(a).is_some_and(|i| row.contains(i)))
+        };
+
+        self.rows
+            .iter()
             .enumerate()
-            .filter_map(|(i, r)| {
-                if indices.contains(&i) {
-                    Some(r.into_iter().map(ToOwned::to_owned).collect::<Vec<bool>>())
-                } else {
-                    None
-                }
+            .filter(|(_, row)| {
+                has_all(row, &implication.premise) && !has_all(row, &implication.conclusion)
             })
-            .reduce(bitand)?;
+            .map(|(o, _)| self.objects.as_slice()[o].to_string())
+            .collect()
+    }
 
-        let output = outputs_named
-            .iter()
-            .zip(der)
-            .filter_map(|(attribute, has)| {
-                if has {
-                    Some(attribute.to_string())
-                } else {
-                    None
-                }
+    pub fn concepts(&self) -> Vec<Concept> {
+        self.concepts_with(Algorithm::NextClosure)
+    }
+
+    /// Lazily generate the concepts in lectic order of their intents
+    pub fn concepts_iter(&self) -> Concepts<'_> {
+        Concepts {
+            context: self,
+            current: Some(self.closure_extents_set(&BitSet::new(self.attributes.len()))),
+        }
+    }
+
+    pub fn concepts_with(&self, algorithm: Algorithm) -> Vec<Concept> {
+        let mut concepts = Vec::new();
+
+        match algorithm {
+            Algorithm::NextClosure => concepts.extend(self.concepts_iter()),
+            Algorithm::CloseByOne => {
+                let extents = BitSet::full(self.objects.len());
+                let intents = self.intents_set(&extents);
+
+                self.close_by_one(extents, intents, 0, &mut |extents, intents| {
+                    concepts.push(Concept {
+                        extents: self.objects.names(extents),
+                        intents: self.attributes.names(intents),
+                    })
+                });
+            }
+            #[cfg(not(feature = "parallel"))]
+            Algorithm::ParallelCloseByOne => concepts = self.concepts_with(Algorithm::CloseByOne),
+            #[cfg(feature = "parallel")]
+            Algorithm::ParallelCloseByOne => {
+                let extents = BitSet::full(self.objects.len());
+                let intents = self.intents_set(&extents);
+
+                concepts = self
+                    .par_close_by_one(extents, intents, 0)
+                    .into_iter()
+                    .map(|(extents, intents)| Concept {
+                        extents: self.objects.names(&extents),
+                        intents: self.attributes.names(&intents),
+                    })
+                    .collect();
+            }
+        }
+
+        concepts
+    }
+
+    /// Generate all concepts below (`extents`, `intents`) by adding attributes from `start`
+    /// onwards. A child is only descended into when the closure did not add an attribute
+    /// before the one just added, so each concept is generated exactly once.
+    fn close_by_one<F>(&self, extents: BitSet, intents: BitSet, start: usize, output: &mut F)
+    where
+        F: FnMut(&BitSet, &BitSet),
+    {
+        output(&extents, &intents);
+
+        for j in start..self.attributes.len() {
+            if intents.contains(j) {
+                continue;
+            }
+
+            let mut child_extents = extents.clone();
+            child_extents.intersect_with(&self.columns[j]);
+            let child_intents = self.intents_set(&child_extents);
+
+            if child_intents.equal_below(&intents, j) {
+                self.close_by_one(child_extents, child_intents, j + 1, output);
+            }
+        }
+    }
+
+    /// Parallel version of [Context::close_by_one] returning the generated concepts
+    #[cfg(feature = "parallel")]
+    fn par_close_by_one(
+        &self,
+        extents: BitSet,
+        intents: BitSet,
+        start: usize,
+    ) -> Vec<(BitSet, BitSet)> {
+        let children: Vec<_> = (start..self.attributes.len())
+            .into_par_iter()
+            .filter(|&j| !intents.contains(j))
+            .filter_map(|j| {
+                let mut child_extents = extents.clone();
+                child_extents.intersect_with(&self.columns[j]);
+                let child_intents = self.intents_set(&child_extents);
+
+                child_intents
+                    .equal_below(&intents, j)
+                    .then(|| self.par_close_by_one(child_extents, child_intents, j + 1))
             })
             .collect();
 
-        Some(output)
+        let mut concepts = vec![(extents, intents)];
+        concepts.extend(children.into_iter().flatten());
+
+        concepts
     }
 
-    pub fn object_has_attribute(&self, object: &str, attribute: &str) -> Option<bool> {
-        let object_index = self.objects.iter().position(|o| o == object)?;
-        let attribute_index = self.attributes.iter().position(|o| o == attribute)?;
+    /// Build the concept lattice by generating the lower neighbours of every concept with
+    /// Lindig's algorithm, starting from the top concept
+    pub fn lattice(&self) -> Lattice {
+        let top = self.closure_extents_set(&BitSet::new(self.attributes.len()));
+        let mut intents = vec![top.clone()];
+        let mut indices = HashMap::from([(top, 0)]);
+        let mut neighbours = Vec::new();
+
+        while neighbours.len() < intents.len() {
+            let lower = self
+                .lower_neighbours(&intents[neighbours.len()])
+                .into_iter()
+                .map(|intent| {
+                    *indices.entry(intent.clone()).or_insert_with(|| {
+                        intents.push(intent);
+                        intents.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>();
 
-        self.array.get((object_index, attribute_index)).copied()
-    }
+            neighbours.push(lower);
+        }
 
-    pub fn concepts(&self) -> Vec<Concept> {
-        let a: Vec<String> = self
-            .attributes()
+        // Lower neighbours have bigger intents, so sorting on size puts them first
+        let mut order: Vec<usize> = (0..intents.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(intents[i].ones().count()));
+
+        let mut position = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            position[old] = new;
+        }
+
+        let nodes = order
             .into_iter()
-            .map(|a| a.to_string())
+            .map(|old| {
+                let mut lower: Vec<_> = neighbours[old].iter().map(|&l| position[l]).collect();
+                lower.sort_unstable();
+
+                (self.concept(&intents[old]), lower)
+            })
             .collect();
 
-        let mut concepts = Vec::new();
-        let mut current = self.closure_extents(&[]);
-
-        while current != None {
-            let c = current.unwrap();
-            concepts.push(Concept {
-                extents: self.extents(&c).unwrap(),
-                intents: c.clone(),
-            });
-            current = next_closure(&a[..], &c[..], |n| self.closure_extents(n));
+        Lattice::from_neighbours(nodes)
+    }
+
+    /// Intents of the lower neighbours of the concept with the given intents
+    fn lower_neighbours(&self, intents: &BitSet) -> Vec<BitSet> {
+        let extents = self.extents_set(intents);
+        let mut min: Vec<bool> = (0..self.attributes.len())
+            .map(|m| !intents.contains(m))
+            .collect();
+        let mut neighbours = Vec::new();
+
+        for m in 0..self.attributes.len() {
+            if intents.contains(m) {
+                continue;
+            }
+
+            let mut child_extents = extents.clone();
+            child_extents.intersect_with(&self.columns[m]);
+            let child_intents = self.intents_set(&child_extents);
+
+            // Only a neighbour if no other minimal attribute was added by the closure
+            let minimal = child_intents
+                .ones()
+                .all(|n| n == m || intents.contains(n) || !min[n]);
+
+            if minimal {
+                neighbours.push(child_intents);
+            } else {
+                min[m] = false;
+            }
         }
 
-        concepts
+        neighbours
+    }
+
+    /// Name the concept with the given intent
+    pub(crate) fn concept(&self, intents: &BitSet) -> Concept {
+        Concept {
+            extents: self.objects.names(&self.extents_set(intents)),
+            intents: self.attributes.names(intents),
+        }
     }
 
     pub fn canonical_basis(&self) -> Vec<Implication> {
+        self.canonical_basis_with(Conclusion::Closure)
+    }
+
+    /// Canonical basis with conclusions in the given form
+    pub fn canonical_basis_with(&self, conclusion: Conclusion) -> Vec<Implication> {
         let mut l = Vec::new();
-        let mut a = Vec::new();
-        let m: Vec<_> = self
-            .attributes()
-            .into_iter()
-            .map(|a| a.to_string())
-            .collect();
+        let mut a = BitSet::new(self.attributes.len());
+        let m = BitSet::full(self.attributes.len());
 
         while a != m {
-            let closure = self.closure_extents(&a).unwrap();
-            if !a.iter().all(|x| closure.contains(x)) || !closure.iter().all(|x| a.contains(x)) {
-                l.push(Implication {
+            let closure = self.closure_extents_set(&a);
+            if closure != a {
+                l.push(IndexImplication {
                     premise: a.clone(),
                     conclusion: closure,
                 });
             }
 
-            let next = next_closure(&m[..], &a, |n| Some(preclosure_operator(&l, n)));
+            let next = next_closure_set(&a, |n| preclosure_operator(&l, n));
 
             if next.is_none() {
                 break;
@@ -193,30 +390,44 @@ impl Context {
             a = next.unwrap();
         }
 
-        l
+        l.iter()
+            .map(|i| {
+                let implication = i.to_named(&self.attributes);
+
+                match conclusion {
+                    Conclusion::Closure => implication,
+                    Conclusion::WithoutPremise => implication.without_premise(),
+                }
+            })
+            .collect()
     }
 
+    /// Explore the attribute implications with an oracle that either accepts an implication,
+    /// returning `None`, or supplies a counterexample object with the attributes it has
+    /// besides the premise. Counterexamples are added like [Context::add_object], so a taken
+    /// object name or an unknown attribute panics.
     pub fn attribute_exploration<F>(mut self, oracle_callback: F) -> (Vec<Implication>, Self)
     where
         F: Fn(&[String], &[String]) -> Option<(Option<String>, Vec<String>)>,
     {
         let mut l = Vec::new();
-        let mut a = Vec::new();
-        let m: Vec<_> = self
-            .attributes()
-            .into_iter()
-            .map(|a| a.to_string())
-            .collect();
+        let mut a = BitSet::new(self.attributes.len());
+        let m = BitSet::full(self.attributes.len());
 
         while a != m {
-            let mut closure = self.closure_extents(&a).unwrap();
+            let mut closure = self.closure_extents_set(&a);
+
+            while closure != a {
+                let mut premise = self.attributes.names(&a);
+                premise.sort_unstable();
+                let conclusion = self.attributes.names(&closure);
 
-            while !a.iter().all(|x| closure.contains(x)) || !closure.iter().all(|x| a.contains(x)) {
-                if let Some((name, mut attributes)) = oracle_callback(&a, &closure) {
-                    attributes.append(&mut a.clone());
-                    self.add_object(name, &attributes).unwrap();
+                if let Some((name, mut attributes)) = oracle_callback(&premise, &conclusion) {
+                    attributes.extend(premise);
+                    self.add_object(name, &attributes)
+                        .expect("invalid counterexample");
                 } else {
-                    l.push(Implication {
+                    l.push(IndexImplication {
                         premise: a.clone(),
                         conclusion: closure,
                     });
@@ -224,10 +435,10 @@ impl Context {
                     break;
                 }
 
-                closure = self.closure_extents(&a).unwrap();
+                closure = self.closure_extents_set(&a);
             }
 
-            let next = next_closure(&m[..], &a, |n| Some(preclosure_operator(&l, n)));
+            let next = next_closure_set(&a, |n| preclosure_operator(&l, n));
 
             if next.is_none() {
                 break;
@@ -236,54 +447,245 @@ impl Context {
             a = next.unwrap();
         }
 
+        let l = l.iter().map(|i| i.to_named(&self.attributes)).collect();
+
         (l, self)
     }
 
+    /// Append an object having the given attributes, failing if its name is already taken or
+    /// an attribute is unknown. Unnamed objects get the first free number from their position.
     pub fn add_object(&mut self, name: Option<String>, attributes: &[String]) -> Result<()> {
-        if let Some(name) = name {
-            self.objects.push(name);
-        } else {
-            let name = self.objects.len() + 1;
-            self.objects.push(name.to_string());
+        let name = self.new_object_name(name)?;
+        let row = self.attribute_set(attributes)?;
+
+        self.push_object(Some(name), row);
+
+        Ok(())
+    }
+
+    /// Append an attribute held by the given objects, failing if its name is already taken or
+    /// an object is unknown
+    pub fn add_attribute(&mut self, name: String, objects: &[String]) -> Result<()> {
+        if self.attributes.index(&name).is_some() {
+            bail!("attribute '{}' already exists", name);
         }
 
-        let attributes: Vec<_> = self
-            .attributes
-            .iter()
-            .map(|a| attributes.contains(a))
-            .collect();
+        let mut column = BitSet::new(self.objects.len());
+
+        for object in objects {
+            column.insert(self.object_index(object)?);
+        }
+
+        for (o, row) in self.rows.iter_mut().enumerate() {
+            row.push(column.contains(o));
+        }
+
+        self.attributes.push(name);
+        self.columns.push(column);
+
+        Ok(())
+    }
+
+    pub fn remove_object(&mut self, name: &str) -> Result<()> {
+        let index = self.object_index(name)?;
+
+        for column in &mut self.columns {
+            column.shift_out(index);
+        }
+
+        self.objects.remove(index);
+        self.rows.remove(index);
+
+        Ok(())
+    }
+
+    pub fn remove_attribute(&mut self, name: &str) -> Result<()> {
+        let index = self.attribute_index(name)?;
+
+        for row in &mut self.rows {
+            row.shift_out(index);
+        }
+
+        self.attributes.remove(index);
+        self.columns.remove(index);
+
+        Ok(())
+    }
+
+    pub fn rename_object(&mut self, name: &str, new_name: String) -> Result<()> {
+        let index = self.object_index(name)?;
+
+        if self.objects.index(&new_name).is_some() {
+            bail!("object '{}' already exists", new_name);
+        }
+
+        self.objects.rename(index, new_name);
+
+        Ok(())
+    }
 
-        self.array.append(
-            Axis(0),
-            ArrayView::from_shape((1, attributes.len()), &attributes)?,
-        )?;
+    pub fn rename_attribute(&mut self, name: &str, new_name: String) -> Result<()> {
+        let index = self.attribute_index(name)?;
+
+        if self.attributes.index(&new_name).is_some() {
+            bail!("attribute '{}' already exists", new_name);
+        }
+
+        self.attributes.rename(index, new_name);
 
         Ok(())
     }
+
+    /// Reorder the attributes, which changes the lectic order of the generated concepts and
+    /// implications. `order` must list every attribute exactly once.
+    pub fn reorder_attributes(&mut self, order: &[String]) -> Result<()> {
+        let mut seen = BitSet::new(self.attributes.len());
+        let mut attributes = Vec::with_capacity(order.len());
+
+        for name in order {
+            let index = self.attribute_index(name)?;
+
+            if seen.contains(index) {
+                bail!("attribute '{}' is listed more than once", name);
+            }
+
+            seen.insert(index);
+            attributes.push(index);
+        }
+
+        if attributes.len() != self.attributes.len() {
+            bail!(
+                "expected all {} attributes, got {}",
+                self.attributes.len(),
+                attributes.len()
+            );
+        }
+
+        let objects: Vec<usize> = (0..self.objects.len()).collect();
+
+        *self = self.select(&objects, &attributes);
+
+        Ok(())
+    }
+
+    /// The given object name if it is free, or the first free number from the object count
+    fn new_object_name(&self, name: Option<String>) -> Result<String> {
+        match name {
+            Some(name) if self.objects.index(&name).is_some() => {
+                bail!("object '{}' already exists", name)
+            }
+            Some(name) => Ok(name),
+            None => Ok(self.free_object_name()),
+        }
+    }
+
+    fn free_object_name(&self) -> String {
+        (self.objects.len() + 1..)
+            .map(|i| i.to_string())
+            .find(|n| self.objects.index(n).is_none())
+            .expect("fewer objects than numbers")
+    }
+
+    fn attribute_set(&self, attributes: &[String]) -> Result<BitSet> {
+        let mut set = BitSet::new(self.attributes.len());
+
+        for attribute in attributes {
+            set.insert(self.attribute_index(attribute)?);
+        }
+
+        Ok(set)
+    }
+
+    fn object_index(&self, name: &str) -> Result<usize> {
+        self.objects
+            .index(name)
+            .ok_or_else(|| anyhow!("unknown object '{}'", name))
+    }
+
+    fn attribute_index(&self, name: &str) -> Result<usize> {
+        self.attributes
+            .index(name)
+            .ok_or_else(|| anyhow!("unknown attribute '{}'", name))
+    }
+
+    /// Subcontext of the given objects and attributes, in the given order
+    fn select(&self, objects: &[usize], attributes: &[usize]) -> Self {
+        let mut context = Self::with_attributes(
+            attributes
+                .iter()
+                .map(|&a| self.attributes.as_slice()[a].to_string())
+                .collect(),
+        );
+
+        for &o in objects {
+            let mut row = BitSet::new(attributes.len());
+
+            for (i, &a) in attributes.iter().enumerate() {
+                if self.rows[o].contains(a) {
+                    row.insert(i);
+                }
+            }
+
+            context.push_object(Some(self.objects.as_slice()[o].to_string()), row);
+        }
+
+        context
+    }
+
+    /// Append an object row, keeping the column view in sync
+    pub(crate) fn push_object(&mut self, name: Option<String>, row: BitSet) {
+        let name = name.unwrap_or_else(|| self.free_object_name());
+
+        for (a, column) in self.columns.iter_mut().enumerate() {
+            column.push(row.contains(a));
+        }
+
+        self.objects.push(name);
+        self.rows.push(row);
+    }
+
+    #[cfg(test)]
+    fn set(&mut self, object: usize, attribute: usize) {
+        self.rows[object].insert(attribute);
+        self.columns[attribute].insert(object);
+    }
 }
 
-fn bitand(accum: Vec<bool>, new: Vec<bool>) -> Vec<bool> {
-    accum.iter().zip(new).map(|(a, n)| a & n).collect()
+/// Iterator over the concepts of a [Context] using NextClosure
+pub struct Concepts<'a> {
+    context: &'a Context,
+    current: Option<BitSet>,
+}
+
+impl Iterator for Concepts<'_> {
+    type Item = Concept;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.take()?;
+
+        self.current = next_closure_set(&current, |n| self.context.closure_extents_set(n));
+
+        Some(self.context.concept(&current))
+    }
 }
 
 impl fmt::Display for Context {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Print headers
         write!(f, "  ")?;
-        let shape = self.array.shape();
-        let objects = shape[0];
-        let attributes = shape[1];
+        let objects = self.objects.len();
+        let attributes = self.attributes.len();
 
         for a in 0..attributes {
-            write!(f, "| {} ", self.attributes[a])?;
+            write!(f, "| {} ", self.attributes.as_slice()[a])?;
         }
         // Print each row
         for r in 0..objects {
             writeln!(f)?;
 
-            write!(f, "{} ", self.objects[r])?;
+            write!(f, "{} ", self.objects.as_slice()[r])?;
             for a in 0..attributes {
-                let symbol = if self.array[[r, a]] { "X" } else { " " };
+                let symbol = if self.rows[r].contains(a) { "X" } else { " " };
                 write!(f, "| {} ", symbol)?;
             }
         }
@@ -294,15 +696,15 @@ impl fmt::Display for Context {
 
 #[cfg(test)]
 mod tests {
-    use crate::{implications::Implication, Concept};
+    use crate::{Concept, Conclusion, Implication, Lattice};
 
-    use super::Context;
+    use super::{Algorithm, Context};
 
     #[test]
     fn display() {
         let mut context = Context::new(4, 3);
 
-        context.array[[2, 2]] = true;
+        context.set(2, 2);
 
         let actual = format!("{}", context);
         let expected = r#"  | 1 | 2 | 3 
@@ -324,7 +726,7 @@ mod tests {
         .unwrap();
 
         assert!(
-            context.array[[0, 1]],
+            context.rows[0].contains(1),
             "ponds should be artificial {}",
             context
         );
@@ -359,6 +761,45 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn from_path() {
+        let context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river, x ,,"#,
+        )
+        .unwrap();
+        let dir = std::env::temp_dir().join(format!("fca-from-path-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (file, data) in [
+            ("context.csv", context.to_csv().unwrap()),
+            ("context.CXT", context.to_cxt().unwrap()),
+            ("context.cex", context.to_cex()),
+        ] {
+            let path = dir.join(file);
+            std::fs::write(&path, data).unwrap();
+
+            assert_eq!(Context::from_path(&path).unwrap(), context, "{}", file);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn duplicate_object_names() {
+        let context = Context::from_csv(",a,b\ng,X,\ng,,X").unwrap();
+
+        assert_eq!(context.objects().len(), 2);
+        assert_eq!(context.object_has_attribute("g", "a"), Some(true));
+        // Every row named "g" takes part in the derivation
+        assert_eq!(context.intents(&["g".to_string()]), Some(Vec::new()));
+        assert_eq!(
+            context.extents(&["a".to_string()]),
+            Some(vec!["g".to_string()])
+        );
+    }
+
     #[test]
     fn add_object_unnamed() {
         let mut input = Context::from_csv(
@@ -376,7 +817,7 @@ mod tests {
         .unwrap();
 
         input
-            .add_object(None, &vec!["running".to_string(), "artificial".to_string()])
+            .add_object(None, &["running".to_string(), "artificial".to_string()])
             .unwrap();
 
         assert_eq!(input, expected);
@@ -401,13 +842,156 @@ mod tests {
         input
             .add_object(
                 Some("canal".to_string()),
-                &vec!["running".to_string(), "artificial".to_string()],
+                &["running".to_string(), "artificial".to_string()],
             )
             .unwrap();
 
         assert_eq!(input, expected);
     }
 
+    #[test]
+    fn add_object_duplicate() {
+        let mut context = Context::from_csv(
+            r#",running,artificial
+                1,,X
+                river,X,"#,
+        )
+        .unwrap();
+
+        assert!(context
+            .add_object(Some("river".to_string()), &["running".to_string()])
+            .is_err());
+        assert!(context
+            .add_object(Some("canal".to_string()), &["missing".to_string()])
+            .is_err());
+        // The generated name "3" is free
+        context.add_object(None, &[]).unwrap();
+        assert!(context.add_object(Some("3".to_string()), &[]).is_err());
+        // "5" would be generated next but is taken, so the next free number is used
+        context.add_object(Some("5".to_string()), &[]).unwrap();
+        context.add_object(None, &[]).unwrap();
+        assert_eq!(
+            context.objects().to_vec(),
+            ["1", "river", "3", "5", "6"].map(String::from)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid counterexample")]
+    fn attribute_exploration_invalid_counterexample() {
+        let context = Context::from_csv(",running,artificial\ncanal,X,X").unwrap();
+
+        context.attribute_exploration(|_, _| Some((Some("canal".to_string()), Vec::new())));
+    }
+
+    #[test]
+    fn add_attribute() {
+        let mut context = Context::from_csv(
+            r#",running,artificial
+                pond,,X
+                river,X,"#,
+        )
+        .unwrap();
+        let expected = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river,X,,"#,
+        )
+        .unwrap();
+
+        context
+            .add_attribute("small".to_string(), &["pond".to_string()])
+            .unwrap();
+
+        assert_eq!(context, expected);
+        assert!(context.add_attribute("small".to_string(), &[]).is_err());
+        assert!(context
+            .add_attribute("still".to_string(), &["lake".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn remove() {
+        let mut context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river,X,,
+                canal,X,X,"#,
+        )
+        .unwrap();
+        let expected = Context::from_csv(
+            r#",running,small
+                pond,,X
+                canal,X,"#,
+        )
+        .unwrap();
+
+        context.remove_object("river").unwrap();
+        context.remove_attribute("artificial").unwrap();
+
+        assert_eq!(context, expected);
+        assert!(context.remove_object("river").is_err());
+        assert!(context.remove_attribute("artificial").is_err());
+    }
+
+    #[test]
+    fn rename() {
+        let mut context = Context::from_csv(
+            r#",running,artificial
+                pond,,X
+                river,X,"#,
+        )
+        .unwrap();
+        let expected = Context::from_csv(
+            r#",flowing,artificial
+                lake,,X
+                river,X,"#,
+        )
+        .unwrap();
+
+        context.rename_object("pond", "lake".to_string()).unwrap();
+        context
+            .rename_attribute("running", "flowing".to_string())
+            .unwrap();
+
+        assert_eq!(context, expected);
+        assert_eq!(
+            context.object_has_attribute("lake", "artificial"),
+            Some(true)
+        );
+        assert!(context.rename_object("pond", "sea".to_string()).is_err());
+        assert!(context.rename_object("lake", "river".to_string()).is_err());
+        assert!(context
+            .rename_attribute("flowing", "artificial".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn reorder_attributes() {
+        let mut context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river,X,,"#,
+        )
+        .unwrap();
+        let expected = Context::from_csv(
+            r#",small,running,artificial
+                pond,X,,X
+                river,,X,"#,
+        )
+        .unwrap();
+        let order = ["small", "running", "artificial"].map(String::from);
+
+        context.reorder_attributes(&order).unwrap();
+
+        assert_eq!(context, expected);
+        assert_eq!(context.concepts()[1].intents, vec!["running".to_string()]);
+        assert!(context.reorder_attributes(&order[..2]).is_err());
+        assert!(context
+            .reorder_attributes(&["small", "small", "running"].map(String::from))
+            .is_err());
+    }
+
     #[test]
     fn intents() {
         let context = Context::from_csv(
@@ -716,6 +1300,153 @@ mod tests {
         assert_eq!(context.concepts(), expected);
     }
 
+    #[test]
+    fn concepts_iter() {
+        let context = Context::from_csv(
+            r#",a,b,c,d,e
+              1, ,x, ,x, 
+              2, ,x, , ,x
+              3, , ,x, , 
+              4,x,x,x, , 
+              5, , , ,x, 
+              6, ,x,x, , 
+              7, , , , ,x"#,
+        )
+        .unwrap();
+
+        let mut iter = context.concepts_iter();
+
+        assert_eq!(
+            iter.next(),
+            Some(Concept {
+                intents: Vec::new(),
+                extents: vec![
+                    "1".to_string(),
+                    "2".to_string(),
+                    "3".to_string(),
+                    "4".to_string(),
+                    "5".to_string(),
+                    "6".to_string(),
+                    "7".to_string(),
+                ],
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Concept {
+                intents: vec!["e".to_string()],
+                extents: vec!["2".to_string(), "7".to_string()],
+            })
+        );
+        assert_eq!(iter.count(), 8);
+        assert_eq!(
+            context.concepts_iter().collect::<Vec<_>>(),
+            context.concepts()
+        );
+    }
+
+    #[test]
+    fn concepts_close_by_one() {
+        let context = Context::from_csv(
+            r#",a,b,c,d,e,f
+              1, ,x,x, ,x,x
+              2, ,x,x, , , 
+              3, , , , , , 
+              4, , , , ,x,x
+              5, , ,x, ,x,x
+              6, ,x, , , , 
+              7, , ,x, ,x,x
+              8, , ,x,x, ,x
+              9, , ,x, ,x,x
+             10, ,x,x,x,x,x"#,
+        )
+        .unwrap();
+
+        let expected = context.concepts();
+        let actual = context.concepts_with(Algorithm::CloseByOne);
+
+        assert_eq!(actual.len(), expected.len());
+        assert!(
+            expected.iter().all(|c| actual.contains(c)),
+            "expected {:?} to match {:?}",
+            actual,
+            expected
+        );
+        assert_eq!(actual[0], expected[0], "top concept should come first");
+    }
+
+    #[test]
+    fn concepts_parallel_close_by_one() {
+        let context = Context::from_csv(
+            r#",a,b,c,d,e
+              1, ,x, ,x, 
+              2, ,x, , ,x
+              3, , ,x, , 
+              4,x,x,x, , 
+              5, , , ,x, 
+              6, ,x,x, , 
+              7, , , , ,x"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            context.concepts_with(Algorithm::ParallelCloseByOne),
+            context.concepts_with(Algorithm::CloseByOne)
+        );
+    }
+
+    #[test]
+    fn lattice() {
+        let context = Context::from_csv(
+            r#",a,b,c,d,e,f
+              1, ,x,x, ,x,x
+              2, ,x,x, , , 
+              3, , , , , , 
+              4, , , , ,x,x
+              5, , ,x, ,x,x
+              6, ,x, , , , 
+              7, , ,x, ,x,x
+              8, , ,x,x, ,x
+              9, , ,x, ,x,x
+             10, ,x,x,x,x,x"#,
+        )
+        .unwrap();
+
+        let actual = context.lattice();
+        let expected = Lattice::from(context.concepts());
+
+        assert_eq!(actual.len(), expected.len());
+        assert_eq!(actual.edge_count(), expected.edge_count());
+
+        for (index, concept) in actual.concepts().enumerate() {
+            let expected_index = expected.find_by_intents(&concept.intents).unwrap();
+            let mut lower: Vec<_> = actual
+                .lower_neighbours(index)
+                .unwrap()
+                .iter()
+                .map(|&l| {
+                    assert!(l < index, "lower neighbours should come first");
+                    expected
+                        .find_by_intents(&actual.concept(l).unwrap().intents)
+                        .unwrap()
+                })
+                .collect();
+            lower.sort_unstable();
+
+            assert_eq!(
+                expected.concept(expected_index),
+                Some(concept),
+                "extents should match"
+            );
+            assert_eq!(
+                expected.lower_neighbours(expected_index),
+                Some(&lower[..]),
+                "lower neighbours of {:?}",
+                concept
+            );
+        }
+    }
+
     #[test]
     fn canonical_basis() {
         let context = Context::from_csv(
@@ -768,6 +1499,61 @@ mod tests {
         ];
 
         assert_eq!(context.canonical_basis(), expected);
+        assert_eq!(context.canonical_basis_with(Conclusion::Closure), expected);
+        assert_eq!(
+            context.canonical_basis_with(Conclusion::WithoutPremise),
+            vec![
+                Implication {
+                    premise: vec!["d".to_string(), "e".to_string()],
+                    conclusion: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                },
+                Implication {
+                    premise: vec!["c".to_string(), "e".to_string()],
+                    conclusion: vec!["a".to_string(), "b".to_string(), "d".to_string()],
+                },
+                Implication {
+                    premise: vec!["c".to_string(), "d".to_string()],
+                    conclusion: vec!["a".to_string(), "b".to_string(), "e".to_string()],
+                },
+                Implication {
+                    premise: vec!["a".to_string()],
+                    conclusion: vec!["b".to_string(), "c".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn counterexamples() {
+        let context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river,X,,
+                canal,X,X,
+                lake,,,X"#,
+        )
+        .unwrap();
+        let implication = |s: &str| s.parse::<Implication>().unwrap();
+
+        assert_eq!(
+            context.counterexamples(&implication("{running} -> {artificial}")),
+            vec!["river".to_string()]
+        );
+        assert_eq!(
+            context.counterexamples(&implication("{} -> {small}")),
+            vec!["river".to_string(), "canal".to_string()]
+        );
+        assert_eq!(
+            context.counterexamples(&implication("{small} -> {missing}")),
+            vec!["pond".to_string(), "lake".to_string()]
+        );
+        assert!(!context.respects(&implication("{running} -> {artificial}")));
+        assert!(context.respects(&implication("{running, small} -> {artificial}")));
+        assert!(context.respects(&implication("{missing} -> {running}")));
+
+        for implication in context.canonical_basis() {
+            assert!(context.respects(&implication), "{}", implication);
+        }
     }
 
     #[test]
@@ -788,9 +1574,9 @@ mod tests {
         .unwrap();
 
         let (basis, new) = context.attribute_exploration(|premise, conclusion| {
-            let premise = premise.into_iter().map(|s| s.as_str()).collect::<Vec<_>>();
+            let premise = premise.iter().map(|s| s.as_str()).collect::<Vec<_>>();
             let conclusion = conclusion
-                .into_iter()
+                .iter()
                 .map(|s| s.as_str())
                 .collect::<Vec<_>>();
 
@@ -948,9 +1734,9 @@ mod tests {
         .unwrap();
 
         let (basis, new) = context.attribute_exploration(|premise, conclusion| {
-            let premise = premise.into_iter().map(|s| s.as_str()).collect::<Vec<_>>();
+            let premise = premise.iter().map(|s| s.as_str()).collect::<Vec<_>>();
             let conclusion = conclusion
-                .into_iter()
+                .iter()
                 .map(|s| s.as_str())
                 .collect::<Vec<_>>();
 
@@ -0,0 +1,88 @@
+use crate::bitset::BitSet;
+
+use super::Context;
+
+/// Arrow relation between a non-incident object g and attribute m
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Arrow {
+    /// g ↗ m: every attribute with a larger extent than m belongs to g
+    Up,
+    /// g ↙ m: every object with a larger intent than g has m
+    Down,
+    /// g ↕ m: both g ↗ m and g ↙ m
+    Double,
+}
+
+impl Context {
+    /// Arrow relations of all non-incident object and attribute pairs that have one, ordered
+    /// by object and then attribute
+    pub fn arrow_relations(&self) -> Vec<(String, String, Arrow)> {
+        let mut arrows = Vec::new();
+
+        for (g, row) in self.rows.iter().enumerate() {
+            for (m, column) in self.columns.iter().enumerate() {
+                if row.contains(m) {
+                    continue;
+                }
+
+                let down = strictly_above(row, &self.rows).all(|h| column.contains(h));
+                let up = strictly_above(column, &self.columns).all(|n| row.contains(n));
+
+                let arrow = match (up, down) {
+                    (true, true) => Arrow::Double,
+                    (true, false) => Arrow::Up,
+                    (false, true) => Arrow::Down,
+                    (false, false) => continue,
+                };
+
+                arrows.push((
+                    self.objects.as_slice()[g].to_string(),
+                    self.attributes.as_slice()[m].to_string(),
+                    arrow,
+                ));
+            }
+        }
+
+        arrows
+    }
+}
+
+/// Indices of the lanes that strictly contain `lane`
+fn strictly_above<'a>(lane: &'a BitSet, lanes: &'a [BitSet]) -> impl Iterator<Item = usize> + 'a {
+    lanes
+        .iter()
+        .enumerate()
+        .filter(move |(_, other)| *other != lane && lane.is_subset(other))
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Arrow, Context};
+
+    #[test]
+    fn arrow_relations() {
+        let context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river,X,,
+                canal,X,X,
+                lake,,,"#,
+        )
+        .unwrap();
+
+        let actual = context.arrow_relations();
+        let expected = [
+            ("pond", "running", Arrow::Double),
+            ("river", "artificial", Arrow::Double),
+            ("canal", "small", Arrow::Double),
+            ("lake", "running", Arrow::Up),
+            ("lake", "artificial", Arrow::Up),
+        ]
+        .iter()
+        .map(|&(g, m, arrow)| (g.to_string(), m.to_string(), arrow))
+        .collect::<Vec<_>>();
+
+        assert_eq!(actual, expected);
+    }
+}
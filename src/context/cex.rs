@@ -0,0 +1,538 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::{BufReader, Read},
+};
+
+use anyhow::{anyhow, bail, Result};
+use quick_xml::{escape::escape, events::Event, Reader, Writer};
+
+use crate::{bitset::BitSet, names::Names};
+
+use super::Context;
+
+/// Parts of a ConExp `.cex` document besides its first context, such as further contexts
+/// and saved lattices, kept as raw XML so that they can be written back unchanged.
+/// They are not updated when the context is edited.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CexSections {
+    /// Identifier of the first context, referred to by saved lattices
+    identifier: Option<String>,
+    /// Name and identifier of each attribute of the first context, in order
+    attributes: Vec<(String, String)>,
+    contexts: Vec<String>,
+    sections: Vec<String>,
+}
+
+/// Where a captured element is kept
+enum Capture {
+    Context,
+    Section,
+}
+
+impl Context {
+    /// Read the first context of a ConExp `.cex` document.
+    /// Other sections, like saved line diagrams, are skipped; see [Context::from_cex_sections]
+    /// to keep them.
+    pub fn from_cex(data: &str) -> Result<Self> {
+        Self::from_cex_reader(data.as_bytes())
+    }
+
+    /// Stream the first context of a ConExp `.cex` document
+    pub fn from_cex_reader<R: Read>(reader: R) -> Result<Self> {
+        Ok(Self::from_cex_sections_reader(reader)?.0)
+    }
+
+    /// Read the first context of a ConExp `.cex` document along with its other sections
+    pub fn from_cex_sections(data: &str) -> Result<(Self, CexSections)> {
+        Self::from_cex_sections_reader(data.as_bytes())
+    }
+
+    /// Stream the first context of a ConExp `.cex` document along with its other sections
+    pub fn from_cex_sections_reader<R: Read>(reader: R) -> Result<(Self, CexSections)> {
+        let mut reader = Reader::from_reader(BufReader::new(reader));
+        reader.config_mut().trim_text(true);
+
+        let mut buffer = Vec::new();
+        let mut sections = CexSections::default();
+        let mut capture: Option<(Writer<Vec<u8>>, usize, Capture)> = None;
+
+        let mut path: Vec<String> = Vec::new();
+        let mut identifiers: HashMap<String, usize> = HashMap::new();
+        let mut attribute_identifier = None;
+        let mut attributes = Vec::new();
+        let mut objects: Vec<(String, Vec<String>)> = Vec::new();
+        let mut text = String::new();
+        let mut seen_context = false;
+
+        loop {
+            buffer.clear();
+            let event = reader.read_event_into(&mut buffer)?;
+
+            if let Some((writer, depth, _)) = capture.as_mut() {
+                match event {
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) => *depth -= 1,
+                    Event::Eof => bail!("cex data ends inside an element"),
+                    _ => {}
+                }
+
+                writer.write_event(event)?;
+
+                if *depth == 0 {
+                    if let Some((writer, _, target)) = capture.take() {
+                        sections.push(target, writer)?;
+                    }
+                }
+
+                continue;
+            }
+
+            let in_context = path.iter().any(|p| p == "Context");
+            // Parent element and text of a Name element that just ended
+            let mut name_text = None;
+
+            match event {
+                Event::Start(ref e) | Event::Empty(ref e) => {
+                    let name = String::from_utf8(e.local_name().as_ref().to_vec())?;
+                    let start = matches!(event, Event::Start(_));
+
+                    let target = match (path.len(), name.as_str()) {
+                        (1, "Version" | "Contexts") => None,
+                        (1, _) => Some(Capture::Section),
+                        (2, "Context") if seen_context => Some(Capture::Context),
+                        _ => None,
+                    };
+
+                    if let Some(target) = target {
+                        let mut writer = Writer::new(Vec::new());
+                        writer.write_event(event.borrow())?;
+
+                        if start {
+                            capture = Some((writer, 1, target));
+                        } else {
+                            sections.push(target, writer)?;
+                        }
+
+                        continue;
+                    }
+
+                    if name == "Context" {
+                        sections.identifier = e
+                            .try_get_attribute("Identifier")?
+                            .map(|i| i.unescape_value().map(|v| v.to_string()))
+                            .transpose()?;
+                        seen_context = true;
+                    }
+
+                    if in_context {
+                        match name.as_str() {
+                            "Attribute" => {
+                                attribute_identifier = Some(attribute(e, "Identifier")?);
+                            }
+                            "Object" => objects.push((String::new(), Vec::new())),
+                            "HasAttribute" => {
+                                let identifier = attribute(e, "AttributeIdentifier")?;
+                                let object = objects
+                                    .last_mut()
+                                    .ok_or_else(|| anyhow!("HasAttribute outside of an Object"))?;
+
+                                object.1.push(identifier);
+                            }
+                            "Name" if start => text.clear(),
+                            "Name" => name_text = Some((path.last().cloned(), String::new())),
+                            _ => {}
+                        }
+                    }
+
+                    if start {
+                        path.push(name);
+                    }
+                }
+                Event::Text(e) if in_context && path.last().map(String::as_str) == Some("Name") => {
+                    text.push_str(&e.unescape()?);
+                }
+                Event::End(_) => {
+                    if let Some(name) = path.pop() {
+                        if name == "Name" && in_context {
+                            name_text = Some((path.last().cloned(), std::mem::take(&mut text)));
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+
+            match name_text {
+                Some((Some(parent), text)) if parent == "Attribute" => {
+                    let identifier = attribute_identifier
+                        .take()
+                        .ok_or_else(|| anyhow!("attribute name without identifier"))?;
+
+                    identifiers.insert(identifier.clone(), attributes.len());
+                    sections.attributes.push((text.clone(), identifier));
+                    attributes.push(text);
+                }
+                Some((Some(parent), text)) if parent == "Object" => {
+                    if let Some(object) = objects.last_mut() {
+                        object.0 = text;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !seen_context {
+            bail!("cex data does not contain a context");
+        }
+
+        let attributes: Names = attributes.into_iter().collect();
+        let mut context = Self::with_attributes(attributes);
+
+        for (name, intent) in objects {
+            let mut row = BitSet::new(context.attributes.len());
+
+            for identifier in intent {
+                let index = identifiers.get(&identifier).ok_or_else(|| {
+                    anyhow!(
+                        "object '{}' has unknown attribute identifier '{}'",
+                        name,
+                        identifier
+                    )
+                })?;
+
+                row.insert(*index);
+            }
+
+            context.push_object(Some(name), row);
+        }
+
+        Ok((context, sections))
+    }
+
+    /// Write the context as a ConExp `.cex` document
+    pub fn to_cex(&self) -> String {
+        self.to_cex_sections(&CexSections::default())
+    }
+
+    /// Write the context as a ConExp `.cex` document with the given other sections, as read
+    /// by [Context::from_cex_sections]
+    pub fn to_cex_sections(&self, sections: &CexSections) -> String {
+        let mut output = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ConceptualSystem>
+  <Version MajorNumber="1" MinorNumber="0" />
+  <Contexts>
+    <Context Identifier="{}" Type="Binary">
+      <Attributes>
+"#,
+            escape(sections.identifier.as_deref().unwrap_or("0"))
+        );
+
+        let identifiers = sections.attribute_identifiers(self.attributes.as_slice());
+
+        for (identifier, name) in identifiers.iter().zip(self.attributes.as_slice()) {
+            output.push_str(&format!(
+                "        <Attribute Identifier=\"{}\">\n          <Name>{}</Name>\n        </Attribute>\n",
+                escape(identifier.as_str()),
+                escape(name.as_str())
+            ));
+        }
+
+        output.push_str("      </Attributes>\n      <Objects>\n");
+
+        for (name, row) in self.objects.as_slice().iter().zip(&self.rows) {
+            output.push_str(&format!(
+                "        <Object>\n          <Name>{}</Name>\n          <Intent>\n",
+                escape(name.as_str())
+            ));
+
+            for i in row.ones() {
+                output.push_str(&format!(
+                    "            <HasAttribute AttributeIdentifier=\"{}\" />\n",
+                    escape(identifiers[i].as_str())
+                ));
+            }
+
+            output.push_str("          </Intent>\n        </Object>\n");
+        }
+
+        output.push_str("      </Objects>\n    </Context>\n");
+
+        for context in &sections.contexts {
+            output.push_str(&format!("    {}\n", context));
+        }
+
+        output.push_str("  </Contexts>\n");
+
+        if sections.sections.is_empty() {
+            output.push_str("  <RecalculationPolicy Value=\"Clear\" />\n  <Lattices />\n");
+        }
+
+        for section in &sections.sections {
+            output.push_str(&format!("  {}\n", section));
+        }
+
+        output.push_str("</ConceptualSystem>\n");
+
+        output
+    }
+}
+
+impl CexSections {
+    /// Identifiers of the given attributes. Attributes read from the document keep theirs,
+    /// matched by name in order, and others get the smallest unused numbers.
+    fn attribute_identifiers(&self, names: &[String]) -> Vec<String> {
+        let mut read: HashMap<&str, VecDeque<&str>> = HashMap::new();
+
+        for (name, identifier) in &self.attributes {
+            read.entry(name).or_default().push_back(identifier);
+        }
+
+        let taken: HashSet<&str> = self.attributes.iter().map(|(_, i)| i.as_str()).collect();
+        let mut fresh = (0..)
+            .map(|i: usize| i.to_string())
+            .filter(|i| !taken.contains(i.as_str()));
+
+        names
+            .iter()
+            .map(
+                |name| match read.get_mut(name.as_str()).and_then(VecDeque::pop_front) {
+                    Some(identifier) => identifier.to_string(),
+                    None => fresh.next().expect("more numbers than identifiers"),
+                },
+            )
+            .collect()
+    }
+
+    fn push(&mut self, target: Capture, writer: Writer<Vec<u8>>) -> Result<()> {
+        let xml = String::from_utf8(writer.into_inner())?;
+
+        match target {
+            Capture::Context => self.contexts.push(xml),
+            Capture::Section => self.sections.push(xml),
+        }
+
+        Ok(())
+    }
+}
+
+fn attribute(element: &quick_xml::events::BytesStart, key: &str) -> Result<String> {
+    let value = element
+        .try_get_attribute(key)?
+        .ok_or_else(|| anyhow!("missing '{}' on {:?}", key, element.local_name()))?;
+
+    Ok(value.unescape_value()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Context;
+
+    #[test]
+    fn from_cex() {
+        let context = Context::from_cex(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ConceptualSystem>
+  <Version MajorNumber="1" MinorNumber="0" />
+  <Contexts>
+    <Context Identifier="0" Type="Binary">
+      <Attributes>
+        <Attribute Identifier="2">
+          <Name>running</Name>
+        </Attribute>
+        <Attribute Identifier="0">
+          <Name>artificial &amp; small</Name>
+        </Attribute>
+      </Attributes>
+      <Objects>
+        <Object>
+          <Name>pond</Name>
+          <Intent>
+            <HasAttribute AttributeIdentifier="0" />
+          </Intent>
+        </Object>
+        <Object>
+          <Name>river</Name>
+          <Intent>
+            <HasAttribute AttributeIdentifier="2" />
+          </Intent>
+        </Object>
+      </Objects>
+    </Context>
+    <Context Identifier="1" Type="Binary">
+      <Attributes>
+        <Attribute Identifier="0">
+          <Name>ignored</Name>
+        </Attribute>
+      </Attributes>
+    </Context>
+  </Contexts>
+  <RecalculationPolicy Value="Clear" />
+  <Lattices>
+    <Lattice Identifier="0" Description="" ContextIdentifier="0">
+      <LineDiagram Name="" />
+    </Lattice>
+  </Lattices>
+</ConceptualSystem>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            context.attributes().to_vec(),
+            vec!["running".to_string(), "artificial & small".to_string()]
+        );
+        assert_eq!(
+            context.objects().to_vec(),
+            vec!["pond".to_string(), "river".to_string()]
+        );
+        assert_eq!(
+            context.object_has_attribute("pond", "artificial & small"),
+            Some(true)
+        );
+        assert_eq!(context.object_has_attribute("pond", "running"), Some(false));
+        assert_eq!(context.object_has_attribute("river", "running"), Some(true));
+    }
+
+    #[test]
+    fn to_cex() {
+        let context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river, x ,,
+                canal,X,X,"#,
+        )
+        .unwrap();
+
+        let actual = Context::from_cex(&context.to_cex()).unwrap();
+
+        assert_eq!(actual, context);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown attribute identifier '7'")]
+    fn from_cex_unknown_attribute() {
+        Context::from_cex(
+            r#"<ConceptualSystem><Contexts><Context Identifier="0" Type="Binary">
+                <Attributes><Attribute Identifier="0"><Name>a</Name></Attribute></Attributes>
+                <Objects><Object><Name>1</Name><Intent>
+                  <HasAttribute AttributeIdentifier="7" />
+                </Intent></Object></Objects>
+              </Context></Contexts></ConceptualSystem>"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cex_sections() {
+        let (context, sections) = Context::from_cex_sections(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ConceptualSystem>
+  <Version MajorNumber="1" MinorNumber="0" />
+  <Contexts>
+    <Context Identifier="3" Type="Binary">
+      <Attributes>
+        <Attribute Identifier="0"><Name>running</Name></Attribute>
+        <Attribute Identifier="1"><Name></Name></Attribute>
+        <Attribute Identifier="2"><Name /></Attribute>
+      </Attributes>
+      <Objects>
+        <Object>
+          <Name>river</Name>
+          <Intent><HasAttribute AttributeIdentifier="0" /><HasAttribute AttributeIdentifier="2" /></Intent>
+        </Object>
+      </Objects>
+    </Context>
+    <Context Identifier="4" Type="Binary"><Attributes /><Objects /></Context>
+  </Contexts>
+  <RecalculationPolicy Value="Keep" />
+  <Lattices>
+    <Lattice Identifier="0" Description="" ContextIdentifier="3">
+      <LineDiagram Name="waters &amp; more" />
+    </Lattice>
+  </Lattices>
+</ConceptualSystem>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            context.attributes().to_vec(),
+            vec!["running".to_string(), String::new(), String::new()]
+        );
+        assert_eq!(context.object_has_attribute("river", "running"), Some(true));
+
+        let output = context.to_cex_sections(&sections);
+
+        assert!(output.contains(r#"<Context Identifier="3" Type="Binary">"#));
+        assert!(output.contains(
+            r#"<Context Identifier="4" Type="Binary"><Attributes /><Objects /></Context>"#
+        ));
+        assert!(output.contains(r#"<RecalculationPolicy Value="Keep" />"#));
+        assert!(output.contains(r#"<LineDiagram Name="waters &amp; more" />"#));
+        assert!(!output.contains("Clear"));
+
+        let (actual, actual_sections) = Context::from_cex_sections(&output).unwrap();
+
+        assert_eq!(actual, context);
+        assert_eq!(actual_sections, sections);
+    }
+
+    #[test]
+    fn cex_sections_identifiers() {
+        let (mut context, sections) = Context::from_cex_sections(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ConceptualSystem>
+  <Version MajorNumber="1" MinorNumber="0" />
+  <Contexts>
+    <Context Identifier="0" Type="Binary">
+      <Attributes>
+        <Attribute Identifier="7"><Name>running</Name></Attribute>
+        <Attribute Identifier="0"><Name>artificial</Name></Attribute>
+        <Attribute Identifier="12"><Name>small</Name></Attribute>
+      </Attributes>
+      <Objects>
+        <Object>
+          <Name>pond</Name>
+          <Intent><HasAttribute AttributeIdentifier="0" /><HasAttribute AttributeIdentifier="12" /></Intent>
+        </Object>
+        <Object>
+          <Name>river</Name>
+          <Intent><HasAttribute AttributeIdentifier="7" /></Intent>
+        </Object>
+      </Objects>
+    </Context>
+  </Contexts>
+  <RecalculationPolicy Value="Keep" />
+  <Lattices>
+    <Lattice Identifier="0" Description="" ContextIdentifier="0">
+      <LineDiagram Name="">
+        <AttributeLabelStyle AttributeIdentifier="12" />
+      </LineDiagram>
+    </Lattice>
+  </Lattices>
+</ConceptualSystem>"#,
+        )
+        .unwrap();
+
+        let output = context.to_cex_sections(&sections);
+
+        assert!(output.contains("<Attribute Identifier=\"7\">\n          <Name>running</Name>"));
+        assert!(output.contains("<Attribute Identifier=\"12\">\n          <Name>small</Name>"));
+        assert!(output.contains(r#"<HasAttribute AttributeIdentifier="12" />"#));
+        assert!(output.contains(r#"<AttributeLabelStyle AttributeIdentifier="12" />"#));
+
+        let (actual, actual_sections) = Context::from_cex_sections(&output).unwrap();
+
+        assert_eq!(actual, context);
+        assert_eq!(actual_sections, sections);
+
+        context.remove_attribute("artificial").unwrap();
+        context
+            .add_attribute("still".to_string(), &["pond".to_string()])
+            .unwrap();
+        let output = context.to_cex_sections(&sections);
+
+        assert!(output.contains("<Attribute Identifier=\"12\">\n          <Name>small</Name>"));
+        assert!(output.contains("<Attribute Identifier=\"1\">\n          <Name>still</Name>"));
+        assert_eq!(Context::from_cex(&output).unwrap(), context);
+    }
+}
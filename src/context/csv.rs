@@ -0,0 +1,286 @@
+use ::csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use anyhow::{bail, Result};
+use std::io::Read;
+
+use crate::{bitset::BitSet, names::Names};
+
+use super::Context;
+
+/// Dialect used to read and write a [Context] as CSV
+///
+/// The default reads the first row as attribute names, the first column as object names and
+/// `X` / `x` as a cross, matching [Context::from_csv].
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    delimiter: u8,
+    markers: Vec<String>,
+    has_headers: bool,
+    object_column: Option<usize>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            markers: vec!["X".to_string()],
+            has_headers: true,
+            object_column: Some(0),
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Values marking an object as having an attribute, compared case-insensitively.
+    /// The first marker is used when writing.
+    pub fn markers<I, S>(mut self, markers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.markers = markers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether the first row holds the attribute names. Without it attributes are numbered.
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Column holding the object names. Without it objects are numbered.
+    pub fn object_column(mut self, object_column: Option<usize>) -> Self {
+        self.object_column = object_column;
+        self
+    }
+
+    fn is_marker(&self, value: &str) -> bool {
+        let value = value.trim().to_lowercase();
+
+        self.markers.iter().any(|m| m.to_lowercase() == value)
+    }
+
+    /// Split a record into the object name and the attribute values
+    fn split<'r>(&self, record: &'r StringRecord) -> Result<(Option<&'r str>, Vec<&'r str>)> {
+        match self.object_column {
+            Some(column) if column >= record.len() => {
+                bail!(
+                    "object column {} is out of range for a record with {} fields",
+                    column,
+                    record.len()
+                )
+            }
+            Some(column) => {
+                let values = record
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, v)| (i != column).then_some(v))
+                    .collect();
+
+                Ok((record.get(column), values))
+            }
+            None => Ok((None, record.iter().collect())),
+        }
+    }
+
+    /// Inverse of [CsvOptions::split]
+    fn join<'r>(&self, name: &'r str, mut values: Vec<&'r str>) -> Result<Vec<&'r str>> {
+        if let Some(column) = self.object_column {
+            if column > values.len() {
+                bail!(
+                    "object column {} is out of range for a record with {} fields",
+                    column,
+                    values.len() + 1
+                );
+            }
+
+            values.insert(column, name);
+        }
+
+        Ok(values)
+    }
+}
+
+impl Context {
+    pub fn from_csv(data: &str) -> Result<Self> {
+        Self::from_csv_with(data, &CsvOptions::default())
+    }
+
+    pub fn from_csv_with(data: &str, options: &CsvOptions) -> Result<Self> {
+        Self::from_csv_reader_with(data.as_bytes(), options)
+    }
+
+    /// Stream CSV records straight into the incidence without buffering the whole input
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self> {
+        Self::from_csv_reader_with(reader, &CsvOptions::default())
+    }
+
+    pub fn from_csv_reader_with<R: Read>(reader: R, options: &CsvOptions) -> Result<Self> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .has_headers(options.has_headers)
+            .from_reader(reader);
+
+        let mut context = None;
+
+        if options.has_headers {
+            let (_, headers) = options.split(reader.headers()?)?;
+            let headers: Names = headers.iter().map(|h| h.trim().to_string()).collect();
+
+            context = Some(Self::with_attributes(headers));
+        }
+
+        for record in reader.into_records() {
+            let record = record?;
+            let (name, values) = options.split(&record)?;
+
+            let context = context.get_or_insert_with(|| {
+                Self::with_attributes((1..=values.len()).map(|i| i.to_string()).collect())
+            });
+            let mut row = BitSet::new(context.attributes.len());
+
+            for (i, v) in values.into_iter().enumerate() {
+                if options.is_marker(v) {
+                    row.insert(i);
+                }
+            }
+
+            context.push_object(name.map(|n| n.trim().to_string()), row);
+        }
+
+        Ok(context.unwrap_or_else(|| Self::with_attributes(Names::default())))
+    }
+
+    pub fn to_csv(&self) -> Result<String> {
+        self.to_csv_with(&CsvOptions::default())
+    }
+
+    pub fn to_csv_with(&self, options: &CsvOptions) -> Result<String> {
+        let mut writer = WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .from_writer(Vec::new());
+        let marker = options.markers.first().map_or("X", String::as_str);
+
+        if options.has_headers {
+            let headers = self
+                .attributes
+                .as_slice()
+                .iter()
+                .map(String::as_str)
+                .collect();
+            writer.write_record(options.join("", headers)?)?;
+        }
+
+        for (name, row) in self.objects.as_slice().iter().zip(&self.rows) {
+            let values = (0..self.attributes.len())
+                .map(|a| if row.contains(a) { marker } else { "" })
+                .collect();
+
+            writer.write_record(options.join(name, values)?)?;
+        }
+
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Context, CsvOptions};
+
+    #[test]
+    fn from_csv_with() {
+        let options = CsvOptions::new()
+            .delimiter(b';')
+            .markers(["1", "true", "✓"])
+            .object_column(Some(3));
+        let context = Context::from_csv_with(
+            "running;artificial;small;
+                0;1;TRUE;pond
+                ✓;0;;river",
+            &options,
+        )
+        .unwrap();
+
+        let expected = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river, x ,,"#,
+        )
+        .unwrap();
+
+        assert_eq!(context, expected);
+    }
+
+    #[test]
+    fn from_csv_without_names() {
+        let options = CsvOptions::new().has_headers(false).object_column(None);
+        let context = Context::from_csv_with(",X\nX,", &options).unwrap();
+
+        let mut expected = Context::new(2, 2);
+        expected.set(0, 1);
+        expected.set(1, 0);
+
+        assert_eq!(context, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "object column 4 is out of range for a record with 3 fields")]
+    fn from_csv_object_column_out_of_range() {
+        let options = CsvOptions::new().object_column(Some(4));
+
+        Context::from_csv_with(",running,small\npond,,X", &options).unwrap();
+    }
+
+    #[test]
+    fn from_csv_reader() {
+        let data = ",running,artificial\npond,,X\nriver,x,\n";
+
+        let actual = Context::from_csv_reader(std::io::Cursor::new(data)).unwrap();
+
+        assert_eq!(actual, Context::from_csv(data).unwrap());
+    }
+
+    #[test]
+    fn to_csv() {
+        let context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river, x ,,"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            context.to_csv().unwrap(),
+            ",running,artificial,small\npond,,X,X\nriver,X,,\n"
+        );
+
+        let options = CsvOptions::new()
+            .delimiter(b'\t')
+            .markers(["1"])
+            .object_column(Some(3));
+        let actual = context.to_csv_with(&options).unwrap();
+
+        assert_eq!(
+            actual,
+            "running\tartificial\tsmall\t\n\t1\t1\tpond\n1\t\t\triver\n"
+        );
+        assert_eq!(Context::from_csv_with(&actual, &options).unwrap(), context);
+    }
+
+    #[test]
+    #[should_panic(expected = "object column 9 is out of range for a record with 3 fields")]
+    fn to_csv_object_column_out_of_range() {
+        let context = Context::from_csv(",running,small\npond,,X").unwrap();
+        let options = CsvOptions::new().object_column(Some(9));
+
+        context.to_csv_with(&options).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::{anyhow, bail, Result};
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::{bitset::BitSet, names::Names};
+
+use super::Context;
+
+impl Context {
+    /// Read a context in the Burmeister `.cxt` format used by ConExp, ToscanaJ and conexp-clj
+    pub fn from_cxt(data: &str) -> Result<Self> {
+        Self::from_cxt_reader(data.as_bytes())
+    }
+
+    /// Stream a context in the Burmeister `.cxt` format
+    pub fn from_cxt_reader<R: Read>(reader: R) -> Result<Self> {
+        // Every line has a fixed position, as names and rows may be empty
+        let mut lines = BufReader::new(reader).lines();
+
+        if next_line(&mut lines, || "cxt data is empty".to_string())?.trim() != "B" {
+            bail!("cxt data should start with a 'B' line");
+        }
+
+        // The context name, which may be followed by a blank line
+        next_line(&mut lines, || "missing context name".to_string())?;
+
+        let mut count = next_line(&mut lines, || "missing object count".to_string())?;
+        if count.trim().is_empty() {
+            count = next_line(&mut lines, || "missing object count".to_string())?;
+        }
+
+        let num_objects: usize = count.trim().parse()?;
+        let num_attributes: usize =
+            next_line(&mut lines, || "missing attribute count".to_string())?
+                .trim()
+                .parse()?;
+
+        if !next_line(&mut lines, || {
+            "missing blank line after the counts".to_string()
+        })?
+        .trim()
+        .is_empty()
+        {
+            bail!("expected a blank line after the counts");
+        }
+
+        let objects = (0..num_objects)
+            .map(|_| {
+                next_line(&mut lines, || {
+                    format!("expected {} object names", num_objects)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let attributes = (0..num_attributes)
+            .map(|_| {
+                next_line(&mut lines, || {
+                    format!("expected {} attribute names", num_attributes)
+                })
+            })
+            .collect::<Result<Names>>()?;
+
+        let mut context = Self::with_attributes(attributes);
+
+        for name in objects {
+            let line = next_line(&mut lines, || {
+                format!("missing incidence row for object '{}'", name)
+            })?;
+            let line = line.trim();
+
+            if line.chars().count() != num_attributes {
+                bail!(
+                    "incidence row for object '{}' should have {} entries",
+                    name,
+                    num_attributes
+                );
+            }
+
+            let mut row = BitSet::new(num_attributes);
+
+            for (i, c) in line.chars().enumerate() {
+                match c {
+                    'X' | 'x' => row.insert(i),
+                    '.' => {}
+                    _ => bail!("unexpected incidence '{}' for object '{}'", c, name),
+                }
+            }
+
+            context.push_object(Some(name), row);
+        }
+
+        Ok(context)
+    }
+
+    /// Write the context in the Burmeister `.cxt` format, failing on names the format cannot
+    /// hold: names with a line break or with leading or trailing whitespace
+    pub fn to_cxt(&self) -> Result<String> {
+        let mut output = format!("B\n\n{}\n{}\n\n", self.objects.len(), self.attributes.len());
+
+        for name in self
+            .objects
+            .as_slice()
+            .iter()
+            .chain(self.attributes.as_slice())
+        {
+            if name.contains('\n') || name.trim() != name {
+                bail!("name {:?} cannot be written to cxt", name);
+            }
+
+            output.push_str(name);
+            output.push('\n');
+        }
+
+        for row in &self.rows {
+            output.extend(
+                (0..self.attributes.len()).map(|a| if row.contains(a) { 'X' } else { '.' }),
+            );
+            output.push('\n');
+        }
+
+        Ok(output)
+    }
+}
+
+/// Next line, or an error built by `missing` at the end of the input
+fn next_line<I>(lines: &mut I, missing: impl FnOnce() -> String) -> Result<String>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    let line = lines.next().ok_or_else(|| anyhow!(missing()))??;
+
+    Ok(line.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Context;
+
+    #[test]
+    fn from_cxt() {
+        let context = Context::from_cxt(
+            "B
+waters
+
+3
+3
+
+pond
+river
+canal
+running
+artificial
+small
+.XX
+X..
+XX.
+",
+        )
+        .unwrap();
+
+        let expected = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river, x ,,
+                canal,X,X,"#,
+        )
+        .unwrap();
+
+        assert_eq!(context, expected);
+    }
+
+    #[test]
+    fn to_cxt() {
+        let context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river, x ,,"#,
+        )
+        .unwrap();
+
+        let actual = context.to_cxt().unwrap();
+        let expected = "B
+
+2
+3
+
+pond
+river
+running
+artificial
+small
+.XX
+X..
+";
+
+        assert_eq!(actual, expected);
+        assert_eq!(Context::from_cxt(&actual).unwrap(), context);
+    }
+
+    #[test]
+    #[should_panic(expected = "incidence row for object 'river' should have 2 entries")]
+    fn from_cxt_short_row() {
+        Context::from_cxt("B\n\n2\n2\n\npond\nriver\nrunning\nsmall\n.X\nX\n").unwrap();
+    }
+
+    #[test]
+    fn cxt_fixed_positions() {
+        let no_attributes = Context::new(2, 0);
+        assert_eq!(
+            Context::from_cxt(&no_attributes.to_cxt().unwrap()).unwrap(),
+            no_attributes
+        );
+
+        let mut empty_names = Context::new(2, 2);
+        empty_names.rename_object("1", String::new()).unwrap();
+        empty_names.rename_attribute("2", String::new()).unwrap();
+        assert_eq!(
+            Context::from_cxt(&empty_names.to_cxt().unwrap()).unwrap(),
+            empty_names
+        );
+
+        let named = Context::from_cxt("B\n2020\n\n1\n1\n\npond\nsmall\nX\n").unwrap();
+        assert_eq!(named.objects().to_vec(), vec!["pond".to_string()]);
+        assert_eq!(named.object_has_attribute("pond", "small"), Some(true));
+    }
+
+    #[test]
+    fn to_cxt_invalid_names() {
+        for name in ["two\nlines", " leading", "trailing ", "  "] {
+            let mut context = Context::new(1, 1);
+            context.rename_attribute("1", name.to_string()).unwrap();
+
+            assert!(context.to_cxt().is_err(), "{:?}", name);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{bitset::BitSet, names::Names};
+
+use super::Context;
+
+/// Objects and attributes removed by [Context::clarify] or [Context::reduce], each mapped to
+/// the remaining items it is represented by.
+/// A clarified item maps to the single item with the same row or column, a reducible item to
+/// the items whose rows or columns intersect to its own.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Reduction {
+    pub objects: BTreeMap<String, Vec<String>>,
+    pub attributes: BTreeMap<String, Vec<String>>,
+}
+
+impl Context {
+    /// Merge objects with identical rows and attributes with identical columns, keeping the
+    /// first of each. The concept lattice keeps its structure.
+    pub fn clarify(&mut self) -> Reduction {
+        let objects = representatives(&self.rows, false);
+        let attributes = representatives(&self.columns, false);
+
+        self.retain(&objects, &attributes)
+    }
+
+    /// Clarify the context and remove reducible objects and attributes, whose row or column is
+    /// the intersection of others. The concept lattice keeps its structure.
+    pub fn reduce(&mut self) -> Reduction {
+        let objects = representatives(&self.rows, true);
+        let attributes = representatives(&self.columns, true);
+
+        self.retain(&objects, &attributes)
+    }
+
+    /// Keep the objects and attributes without representatives, reporting the others
+    fn retain(
+        &mut self,
+        objects: &[Option<Vec<usize>>],
+        attributes: &[Option<Vec<usize>>],
+    ) -> Reduction {
+        let reduction = Reduction {
+            objects: removed(objects, &self.objects),
+            attributes: removed(attributes, &self.attributes),
+        };
+
+        *self = self.select(&kept(objects), &kept(attributes));
+
+        reduction
+    }
+}
+
+/// Representatives of every removed lane, or `None` for the lanes that are kept.
+/// Duplicates are represented by the first equal lane and, when `reduce` is set, reducible
+/// lanes by the minimal irreducible lanes strictly containing them.
+fn representatives(lanes: &[BitSet], reduce: bool) -> Vec<Option<Vec<usize>>> {
+    let mut first: HashMap<&BitSet, usize> = HashMap::new();
+    let mut result: Vec<Option<Vec<usize>>> = lanes
+        .iter()
+        .enumerate()
+        .map(|(i, lane)| {
+            let representative = *first.entry(lane).or_insert(i);
+
+            (representative != i).then(|| vec![representative])
+        })
+        .collect();
+
+    if reduce {
+        let width = lanes.first().map_or(0, BitSet::len);
+        let clarified: Vec<usize> = (0..lanes.len()).filter(|&i| result[i].is_none()).collect();
+        let above = |i: usize| {
+            clarified
+                .iter()
+                .copied()
+                .filter(move |&j| lanes[i] != lanes[j] && lanes[i].is_subset(&lanes[j]))
+        };
+
+        // Reducibility is decided before removing anything, as removing a reducible lane never
+        // changes the intersection of the lanes above another
+        let reducible: Vec<usize> = clarified
+            .iter()
+            .copied()
+            .filter(|&i| {
+                let mut meet = BitSet::full(width);
+                above(i).for_each(|j| meet.intersect_with(&lanes[j]));
+
+                meet == lanes[i]
+            })
+            .collect();
+
+        for &i in &reducible {
+            let irreducible: Vec<usize> = above(i).filter(|j| !reducible.contains(j)).collect();
+            let minimal = irreducible
+                .iter()
+                .copied()
+                .filter(|&j| {
+                    irreducible
+                        .iter()
+                        .all(|&k| k == j || !lanes[k].is_subset(&lanes[j]))
+                })
+                .collect();
+
+            result[i] = Some(minimal);
+        }
+
+        // Duplicates of a reducible lane are represented like it
+        for i in 0..lanes.len() {
+            let representative = first[&lanes[i]];
+
+            if representative != i && reducible.contains(&representative) {
+                result[i] = result[representative].clone();
+            }
+        }
+    }
+
+    result
+}
+
+fn kept(representatives: &[Option<Vec<usize>>]) -> Vec<usize> {
+    (0..representatives.len())
+        .filter(|&i| representatives[i].is_none())
+        .collect()
+}
+
+fn removed(representatives: &[Option<Vec<usize>>], names: &Names) -> BTreeMap<String, Vec<String>> {
+    let names = names.as_slice();
+
+    representatives
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| {
+            let r = r.as_ref()?;
+
+            Some((
+                names[i].to_string(),
+                r.iter().map(|&j| names[j].to_string()).collect(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::Context;
+
+    fn map(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.iter().map(|s| s.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn clarify() {
+        let mut context = Context::from_csv(
+            r#",running,flowing,artificial,small
+                pond,,,X,X
+                river,X,X,,
+                stream,X,X,,
+                canal,X,X,X,"#,
+        )
+        .unwrap();
+        let lattice = context.lattice();
+
+        let reduction = context.clarify();
+
+        let expected = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river,X,,
+                canal,X,X,"#,
+        )
+        .unwrap();
+
+        assert_eq!(context, expected);
+        assert_eq!(reduction.objects, map(&[("stream", &["river"])]));
+        assert_eq!(reduction.attributes, map(&[("flowing", &["running"])]));
+        assert_eq!(context.lattice().len(), lattice.len());
+        assert_eq!(context.lattice().edge_count(), lattice.edge_count());
+    }
+
+    #[test]
+    fn reduce() {
+        let mut context = Context::from_csv(
+            r#",running,flowing,artificial,small,water,still artificial
+                pond,,,X,X,X,X
+                river,X,X,,,X,
+                stream,X,X,,,X,
+                canal,X,X,X,,X,
+                lake,,,,,X,"#,
+        )
+        .unwrap();
+        let lattice = context.lattice();
+
+        let reduction = context.reduce();
+
+        let expected = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river,X,,
+                canal,X,X,"#,
+        )
+        .unwrap();
+
+        assert_eq!(context, expected);
+        assert_eq!(
+            reduction.objects,
+            map(&[("stream", &["river"]), ("lake", &["pond", "river"])])
+        );
+        assert_eq!(
+            reduction.attributes,
+            map(&[
+                ("flowing", &["running"]),
+                ("water", &[]),
+                ("still artificial", &["small"])
+            ])
+        );
+        assert_eq!(context.lattice().len(), lattice.len());
+        assert_eq!(context.lattice().edge_count(), lattice.edge_count());
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{bail, Result};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{bitset::BitSet, names::Names};
+
+use super::Context;
+
+/// Stable serialised form of a [Context], listing the attribute indices of every object
+#[derive(Serialize, Deserialize)]
+struct ContextData {
+    objects: Vec<String>,
+    attributes: Vec<String>,
+    incidence: Vec<Vec<usize>>,
+}
+
+impl Serialize for Context {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ContextData {
+            objects: self.objects.as_slice().to_vec(),
+            attributes: self.attributes.as_slice().to_vec(),
+            incidence: self.rows.iter().map(|r| r.ones().collect()).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Context {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ContextData::deserialize(deserializer)?;
+
+        Context::try_from(data).map_err(D::Error::custom)
+    }
+}
+
+impl TryFrom<ContextData> for Context {
+    type Error = anyhow::Error;
+
+    fn try_from(data: ContextData) -> Result<Self> {
+        if data.objects.len() != data.incidence.len() {
+            bail!(
+                "expected incidence for {} objects, found {}",
+                data.objects.len(),
+                data.incidence.len()
+            );
+        }
+
+        let attributes: Names = data.attributes.into_iter().collect();
+        let mut context = Self::with_attributes(attributes);
+
+        for (name, indices) in data.objects.into_iter().zip(data.incidence) {
+            let mut row = BitSet::new(context.attributes.len());
+
+            for i in indices {
+                if i >= row.len() {
+                    bail!("object '{}' has unknown attribute index {}", name, i);
+                }
+
+                row.insert(i);
+            }
+
+            context.push_object(Some(name), row);
+        }
+
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Context;
+
+    #[test]
+    fn round_trip() {
+        let context = Context::from_csv(
+            r#",running,artificial,small
+                pond,,X,X
+                river, x ,,"#,
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&context).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"objects":["pond","river"],"attributes":["running","artificial","small"],"incidence":[[1,2],[0]]}"#
+        );
+        assert_eq!(serde_json::from_str::<Context>(&json).unwrap(), context);
+    }
+
+    #[test]
+    fn unknown_attribute() {
+        let error = serde_json::from_str::<Context>(
+            r#"{"objects":["pond"],"attributes":["running"],"incidence":[[3]]}"#,
+        )
+        .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("object 'pond' has unknown attribute index 3"));
+    }
+}
@@ -1,99 +1,447 @@
-use std::collections::BTreeSet;
+use anyhow::{anyhow, bail, Error, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
-#[derive(Clone)]
-#[cfg_attr(test, derive(Debug, PartialEq))]
+use crate::{bitset::BitSet, names::Names};
+
+/// Every object having all attributes of the premise also has those of the conclusion
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Implication {
     pub premise: Vec<String>,
     pub conclusion: Vec<String>,
 }
 
-pub(crate) fn preclosure_operator(basis: &[Implication], set: &[String]) -> Vec<String> {
-    if set.is_empty() {
-        return Vec::new();
+/// Form of the conclusions of the implications returned by
+/// [Context::canonical_basis_with](crate::Context::canonical_basis_with)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Conclusion {
+    /// The closure of the premise, so `{a} -> {a, b}`
+    #[default]
+    Closure,
+    /// Only the attributes not already in the premise, so `{a} -> {b}`
+    WithoutPremise,
+}
+
+impl Implication {
+    /// Same implication with the premise attributes removed from the conclusion
+    pub fn without_premise(&self) -> Self {
+        Self {
+            premise: self.premise.clone(),
+            conclusion: self
+                .conclusion
+                .iter()
+                .filter(|c| !self.premise.contains(c))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Same implication with the premise attributes added to the conclusion, ahead of the
+    /// attributes only in the conclusion
+    pub fn with_premise(&self) -> Self {
+        Self {
+            premise: self.premise.clone(),
+            conclusion: self
+                .premise
+                .iter()
+                .chain(self.conclusion.iter().filter(|c| !self.premise.contains(c)))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Implication {
+    /// Written as `{a, b} -> {c}`. Names that are empty, have surrounding whitespace or
+    /// contain `,`, `{`, `}`, `"`, `\` or `->` are quoted, escaping `"` and `\` with a backslash.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let set = |names: &[String]| {
+            names
+                .iter()
+                .map(|n| quote(n))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        write!(
+            f,
+            "{{{}}} -> {{{}}}",
+            set(&self.premise),
+            set(&self.conclusion)
+        )
     }
+}
+
+impl FromStr for Implication {
+    type Err = Error;
+
+    /// Parse the [Display](fmt::Display) form, where unquoted names are trimmed
+    fn from_str(s: &str) -> Result<Self> {
+        let mut rest = s;
+        let premise = attribute_set(&mut rest, s)?;
 
-    let mut x = BTreeSet::from_iter(set.to_vec());
-    let mut stable = false;
-    let mut basis = basis.to_vec();
+        rest = rest
+            .trim_start()
+            .strip_prefix("->")
+            .ok_or_else(|| anyhow!("implication '{}' is missing '->'", s))?;
 
-    while !stable {
-        let new: Vec<_> = basis
-            .drain_filter(|i| i.premise.iter().all(|p| x.contains(p)))
-            .flat_map(|i| i.conclusion)
-            .collect();
+        let conclusion = attribute_set(&mut rest, s)?;
+
+        if !rest.trim().is_empty() {
+            bail!("unexpected '{}' after implication '{}'", rest.trim(), s);
+        }
+
+        Ok(Self {
+            premise,
+            conclusion,
+        })
+    }
+}
 
-        if new.is_empty() {
-            stable = true;
+fn quote(name: &str) -> String {
+    let plain = !name.is_empty()
+        && name.trim() == name
+        && !name.contains([',', '{', '}', '"', '\\'])
+        && !name.contains("->");
+
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Parse a `{a, "b, c"}` attribute set from the start of `rest`, advancing past it
+fn attribute_set(rest: &mut &str, s: &str) -> Result<Vec<String>> {
+    *rest = rest
+        .trim_start()
+        .strip_prefix('{')
+        .ok_or_else(|| anyhow!("attribute sets in '{}' should be enclosed in braces", s))?;
+
+    let mut names = Vec::new();
+
+    if let Some(after) = rest.trim_start().strip_prefix('}') {
+        *rest = after;
+        return Ok(names);
+    }
+
+    loop {
+        *rest = rest.trim_start();
+
+        let name = if let Some(quoted) = rest.strip_prefix('"') {
+            let mut name = String::new();
+            let mut chars = quoted.char_indices();
+
+            loop {
+                match chars.next() {
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, c)) => name.push(c),
+                        None => bail!("unterminated quoted name in '{}'", s),
+                    },
+                    Some((i, '"')) => {
+                        *rest = &quoted[i + 1..];
+                        break;
+                    }
+                    Some((_, c)) => name.push(c),
+                    None => bail!("unterminated quoted name in '{}'", s),
+                }
+            }
+
+            name
         } else {
-            x.extend(new);
+            let end = rest.find([',', '}']).unwrap_or(rest.len());
+            let name = rest[..end].trim();
+
+            if name.is_empty() || name.contains(['{', '"', '\\']) {
+                bail!("invalid attribute name '{}' in '{}'", name, s);
+            }
+
+            *rest = &rest[end..];
+            name.to_string()
+        };
+
+        names.push(name);
+        *rest = rest.trim_start();
+
+        if let Some(after) = rest.strip_prefix(',') {
+            *rest = after;
+        } else if let Some(after) = rest.strip_prefix('}') {
+            *rest = after;
+            return Ok(names);
+        } else {
+            bail!("expected ',' or '}}' after an attribute name in '{}'", s);
+        }
+    }
+}
+
+/// An [Implication] over attribute indices
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(crate) struct IndexImplication {
+    pub premise: BitSet,
+    pub conclusion: BitSet,
+}
+
+impl IndexImplication {
+    /// Index form of `implication` over `attributes`, which must contain all its attributes
+    pub fn from_named(implication: &Implication, attributes: &Names) -> Self {
+        let set = |names: &[String]| {
+            let mut set = BitSet::new(attributes.len());
+            names
+                .iter()
+                .filter_map(|n| attributes.index(n))
+                .for_each(|i| set.insert(i));
+            set
+        };
+
+        Self {
+            premise: set(&implication.premise),
+            conclusion: set(&implication.conclusion),
+        }
+    }
+
+    /// Named form of the implication, listing the premise alphabetically
+    pub fn to_named(&self, attributes: &Names) -> Implication {
+        let mut premise = attributes.names(&self.premise);
+        premise.sort_unstable();
+
+        Implication {
+            premise,
+            conclusion: attributes.names(&self.conclusion),
+        }
+    }
+}
+
+/// Whether `implication` holds in every context respecting all of `implications`
+pub fn entails(implications: &[Implication], implication: &Implication) -> bool {
+    let names = attribute_names(&[implications, std::slice::from_ref(implication)]);
+    let basis = to_indices(implications, &names);
+    let implication = IndexImplication::from_named(implication, &names);
+
+    implication
+        .conclusion
+        .is_subset(&closure(&basis, &implication.premise))
+}
+
+/// Whether both sets of implications hold in exactly the same contexts
+pub fn equivalent(a: &[Implication], b: &[Implication]) -> bool {
+    a.iter().all(|i| entails(b, i)) && b.iter().all(|i| entails(a, i))
+}
+
+/// Equivalent set of implications without redundancy. Premises are reduced to minimal sets
+/// with the same closure, implications following from the others are dropped and conclusions
+/// only keep the attributes the other implications do not derive from the premise.
+pub fn minimal_cover(implications: &[Implication]) -> Vec<Implication> {
+    let names = attribute_names(&[implications]);
+    let mut basis = to_indices(implications, &names);
+
+    // Left reduction against the full closure of every premise
+    for i in 0..basis.len() {
+        let target = closure(&basis, &basis[i].premise);
+        let mut premise = basis[i].premise.clone();
+
+        for a in basis[i].premise.ones() {
+            premise.remove(a);
+
+            if !target.is_subset(&closure(&basis, &premise)) {
+                premise.insert(a);
+            }
+        }
+
+        basis[i] = IndexImplication {
+            premise,
+            conclusion: target,
+        };
+    }
+
+    // Drop the implications the remaining ones already entail
+    let mut i = 0;
+    while i < basis.len() {
+        let implication = basis.remove(i);
+
+        if !implication
+            .conclusion
+            .is_subset(&closure(&basis, &implication.premise))
+        {
+            basis.insert(i, implication);
+            i += 1;
+        }
+    }
+
+    // Right reduction, keeping only what the others do not derive
+    for i in 0..basis.len() {
+        let implication = basis.remove(i);
+        let derived = closure(&basis, &implication.premise);
+        let mut conclusion = implication.conclusion;
+
+        for a in derived.ones() {
+            conclusion.remove(a);
+        }
+
+        basis.insert(
+            i,
+            IndexImplication {
+                premise: implication.premise,
+                conclusion,
+            },
+        );
+    }
+
+    basis.iter().map(|i| i.to_named(&names)).collect()
+}
+
+/// All attributes of the given implication sets, in order of first occurrence
+fn attribute_names(sets: &[&[Implication]]) -> Names {
+    let mut names = Names::default();
+
+    for attribute in sets
+        .iter()
+        .flat_map(|s| s.iter())
+        .flat_map(|i| i.premise.iter().chain(&i.conclusion))
+    {
+        if names.index(attribute).is_none() {
+            names.push(attribute.to_string());
+        }
+    }
+
+    names
+}
+
+fn to_indices(implications: &[Implication], names: &Names) -> Vec<IndexImplication> {
+    implications
+        .iter()
+        .map(|i| IndexImplication::from_named(i, names))
+        .collect()
+}
+
+/// Closure of `set` under the implications of `basis`, except that the empty set is returned
+/// as is
+pub(crate) fn preclosure_operator(basis: &[IndexImplication], set: &BitSet) -> BitSet {
+    if set.is_empty() {
+        return set.clone();
+    }
+
+    closure(basis, set)
+}
+
+/// Closure of `set` under the implications of `basis` using Beeri and Bernstein's LinClosure,
+/// which counts the premise attributes still missing for every implication and so runs in
+/// time linear in the size of the basis
+fn closure(basis: &[IndexImplication], set: &BitSet) -> BitSet {
+    let mut missing = Vec::with_capacity(basis.len());
+    let mut premises_with = vec![Vec::new(); set.len()];
+    let mut closure = set.clone();
+    let mut update: Vec<usize> = set.ones().collect();
+
+    for (i, implication) in basis.iter().enumerate() {
+        let mut count = 0;
+
+        for a in implication.premise.ones() {
+            premises_with[a].push(i);
+            count += 1;
+        }
+
+        missing.push(count);
+
+        if count == 0 {
+            extend(&mut closure, &mut update, &implication.conclusion);
+        }
+    }
+
+    while let Some(a) = update.pop() {
+        for &i in &premises_with[a] {
+            missing[i] -= 1;
+
+            if missing[i] == 0 {
+                extend(&mut closure, &mut update, &basis[i].conclusion);
+            }
         }
     }
 
-    x.into_iter().collect()
+    closure
+}
+
+/// Add the new attributes of `conclusion` to the closure and queue them for processing
+fn extend(closure: &mut BitSet, update: &mut Vec<usize>, conclusion: &BitSet) {
+    for a in conclusion.ones() {
+        if !closure.contains(a) {
+            closure.insert(a);
+            update.push(a);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{preclosure_operator, Implication};
+    use crate::bitset::BitSet;
+
+    use super::{
+        entails, equivalent, minimal_cover, preclosure_operator, Implication, IndexImplication,
+    };
+
+    fn named(premise: &[&str], conclusion: &[&str]) -> Implication {
+        Implication {
+            premise: premise.iter().map(|a| a.to_string()).collect(),
+            conclusion: conclusion.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    // Attributes a, b, c, d as indices 0, 1, 2, 3
+    fn set(indices: &[usize]) -> BitSet {
+        let mut set = BitSet::new(4);
+        indices.iter().for_each(|&i| set.insert(i));
+        set
+    }
+
+    fn implication(premise: &[usize], conclusion: &[usize]) -> IndexImplication {
+        IndexImplication {
+            premise: set(premise),
+            conclusion: set(conclusion),
+        }
+    }
 
     #[test]
     fn preclosure_operator_empty() {
-        let basis = [Implication {
-            premise: vec!["a".to_string()],
-            conclusion: vec!["b".to_string()],
-        }];
-        let set = [];
+        let basis = [implication(&[0], &[1])];
+        let input = set(&[]);
 
-        let actual = preclosure_operator(&basis, &set);
-        let expected: [&str; 0] = [];
+        let actual = preclosure_operator(&basis, &input);
+        let expected = set(&[]);
 
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn preclosure_operator_non_matching() {
-        let basis = [Implication {
-            premise: vec!["a".to_string()],
-            conclusion: vec!["b".to_string()],
-        }];
-        let set = ["c".to_string()];
+        let basis = [implication(&[0], &[1])];
+        let input = set(&[2]);
 
-        let actual = preclosure_operator(&basis, &set);
-        let expected = ["c"];
+        let actual = preclosure_operator(&basis, &input);
+        let expected = set(&[2]);
 
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn preclosure_operator_matching() {
-        let basis = [Implication {
-            premise: vec!["a".to_string()],
-            conclusion: vec!["b".to_string()],
-        }];
-        let set = ["a".to_string()];
+        let basis = [implication(&[0], &[1])];
+        let input = set(&[0]);
 
-        let actual = preclosure_operator(&basis, &set);
-        let expected = ["a", "b"];
+        let actual = preclosure_operator(&basis, &input);
+        let expected = set(&[0, 1]);
 
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn preclosure_operator_recursive() {
-        let basis = [
-            Implication {
-                premise: vec!["a".to_string()],
-                conclusion: vec!["b".to_string()],
-            },
-            Implication {
-                premise: vec!["a".to_string(), "b".to_string()],
-                conclusion: vec!["c".to_string(), "d".to_string()],
-            },
-        ];
-        let set = ["a".to_string()];
+        let basis = [implication(&[0], &[1]), implication(&[0, 1], &[2, 3])];
+        let input = set(&[0]);
 
-        let actual = preclosure_operator(&basis, &set);
-        let expected = ["a", "b", "c", "d"];
+        let actual = preclosure_operator(&basis, &input);
+        let expected = set(&[0, 1, 2, 3]);
 
         assert_eq!(actual, expected);
     }
@@ -101,24 +449,14 @@ mod tests {
     #[test]
     fn preclosure_operator_recursive_multiple() {
         let basis = [
-            Implication {
-                premise: vec!["a".to_string()],
-                conclusion: vec!["b".to_string()],
-            },
-            Implication {
-                premise: vec!["a".to_string(), "b".to_string()],
-                conclusion: vec!["c".to_string(), "d".to_string()],
-            },
-            Implication {
-                premise: vec!["c".to_string()],
-                conclusion: vec!["d".to_string()],
-            },
+            implication(&[0], &[1]),
+            implication(&[0, 1], &[2, 3]),
+            implication(&[2], &[3]),
         ];
-        let set = ["a".to_string()];
+        let input = set(&[0]);
 
-        let actual = preclosure_operator(&basis, &set);
-        // "d" should not appear twice
-        let expected = ["a", "b", "c", "d"];
+        let actual = preclosure_operator(&basis, &input);
+        let expected = set(&[0, 1, 2, 3]);
 
         assert_eq!(actual, expected);
     }
@@ -126,25 +464,147 @@ mod tests {
     #[test]
     fn preclosure_operator_recursive_multiple_unordered() {
         let basis = [
-            Implication {
-                premise: vec!["a".to_string()],
-                conclusion: vec!["b".to_string()],
-            },
-            Implication {
-                premise: vec!["b".to_string(), "a".to_string()],
-                conclusion: vec!["d".to_string(), "c".to_string()],
-            },
-            Implication {
-                premise: vec!["c".to_string()],
-                conclusion: vec!["d".to_string()],
-            },
+            implication(&[0], &[1]),
+            implication(&[1, 0], &[3, 2]),
+            implication(&[2], &[3]),
         ];
-        let set = ["a".to_string()];
+        let input = set(&[0]);
 
-        let actual = preclosure_operator(&basis, &set);
-        // "d" should not appear twice
-        let expected = ["a", "b", "c", "d"];
+        let actual = preclosure_operator(&basis, &input);
+        let expected = set(&[0, 1, 2, 3]);
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn preclosure_operator_empty_premise() {
+        let basis = [implication(&[1, 2], &[3]), implication(&[], &[2])];
+        let input = set(&[1]);
+
+        let actual = preclosure_operator(&basis, &input);
+        let expected = set(&[1, 2, 3]);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn display() {
+        let implication = Implication {
+            premise: vec!["a".to_string(), "b".to_string()],
+            conclusion: vec!["c".to_string()],
+        };
+        let empty = Implication {
+            premise: Vec::new(),
+            conclusion: vec!["small pond".to_string()],
+        };
+
+        assert_eq!(implication.to_string(), "{a, b} -> {c}");
+        assert_eq!(empty.to_string(), "{} -> {small pond}");
+    }
+
+    #[test]
+    fn from_str() {
+        let implication = Implication {
+            premise: vec!["a".to_string(), "b".to_string()],
+            conclusion: vec!["small pond".to_string()],
+        };
+
+        assert_eq!(
+            " {a,b }->{ small pond}".parse::<Implication>().unwrap(),
+            implication
+        );
+        assert_eq!(
+            implication.to_string().parse::<Implication>().unwrap(),
+            implication
+        );
+        assert_eq!(
+            "{} -> {}".parse::<Implication>().unwrap(),
+            Implication {
+                premise: Vec::new(),
+                conclusion: Vec::new(),
+            }
+        );
+        assert!("{a} {b}".parse::<Implication>().is_err());
+        assert!("a -> {b}".parse::<Implication>().is_err());
+        assert!("{a,} -> {b}".parse::<Implication>().is_err());
+        assert!("{a} -> {b} {c}".parse::<Implication>().is_err());
+        assert!(r#"{"a} -> {b}"#.parse::<Implication>().is_err());
+    }
+
+    #[test]
+    fn display_quoted() {
+        let implication = named(
+            &["small, still", "a -> b", ""],
+            &[" padded", r#"say "hi" \ bye"#, "{x}"],
+        );
+
+        assert_eq!(
+            implication.to_string(),
+            r#"{"small, still", "a -> b", ""} -> {" padded", "say \"hi\" \\ bye", "{x}"}"#
+        );
+        assert_eq!(
+            implication.to_string().parse::<Implication>().unwrap(),
+            implication
+        );
+    }
+
+    #[test]
+    fn without_premise() {
+        let implication = named(&["a", "c"], &["a", "b", "c", "d"]);
+
+        assert_eq!(
+            implication.without_premise(),
+            named(&["a", "c"], &["b", "d"])
+        );
+        assert_eq!(
+            implication.without_premise().with_premise(),
+            named(&["a", "c"], &["a", "c", "b", "d"])
+        );
+        assert_eq!(
+            named(&["a"], &["b", "a"]).with_premise(),
+            named(&["a"], &["a", "b"])
+        );
+    }
+
+    fn parse(implications: &[&str]) -> Vec<Implication> {
+        implications.iter().map(|i| i.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn entails_implication() {
+        let implications = parse(&["{a} -> {b}", "{b, c} -> {d}", "{} -> {e}"]);
+
+        assert!(entails(&implications, &named(&["a", "c"], &["d", "e"])));
+        assert!(entails(&implications, &named(&["d"], &["d", "e"])));
+        assert!(!entails(&implications, &named(&["b"], &["a"])));
+        assert!(!entails(&implications, &named(&["a"], &["missing"])));
+        assert!(entails(&[], &named(&["a", "b"], &["a"])));
+    }
+
+    #[test]
+    fn equivalent_sets() {
+        let a = parse(&["{a} -> {b}", "{b} -> {c}"]);
+        let b = parse(&["{a} -> {b, c}", "{b} -> {c}"]);
+        let c = parse(&["{a} -> {c}", "{b} -> {c}"]);
+
+        assert!(equivalent(&a, &b));
+        assert!(!equivalent(&a, &c));
+        assert!(equivalent(&[], &parse(&["{a} -> {a}"])));
+    }
+
+    #[test]
+    fn minimal_cover_reduces() {
+        let implications = parse(&[
+            "{a} -> {b}",
+            "{a, b} -> {c}",
+            "{a} -> {c}",
+            "{c, d} -> {c}",
+            "{b, d} -> {e, b}",
+        ]);
+
+        let cover = minimal_cover(&implications);
+
+        assert_eq!(cover, parse(&["{a} -> {b, c}", "{b, d} -> {e}"]));
+        assert!(equivalent(&cover, &implications));
+    }
 }
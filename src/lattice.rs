@@ -1,16 +1,253 @@
-use std::collections::BinaryHeap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 use crate::Concept;
 
+pub use self::dot::Labels;
+
+mod dot;
+#[cfg(feature = "serde")]
+mod serialize;
+mod svg;
+
 #[cfg_attr(test, derive(Debug, PartialEq))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct Node {
     concept: Concept,
     lower_neighbour_indices: Vec<usize>,
 }
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Lattice {
     nodes: Vec<Node>,
+    /// Node index of each concept by its sorted intents
+    #[cfg_attr(feature = "serde", serde(skip))]
+    intents: HashMap<Vec<String>, usize>,
+    /// Node index of each concept by its sorted extents
+    #[cfg_attr(feature = "serde", serde(skip))]
+    extents: HashMap<Vec<String>, usize>,
+}
+
+impl Lattice {
+    /// Lattice from concepts with the indices of their lower neighbours, where every lower
+    /// neighbour comes before the concept itself
+    pub(crate) fn from_neighbours(nodes: Vec<(Concept, Vec<usize>)>) -> Self {
+        let nodes = nodes
+            .into_iter()
+            .map(|(concept, lower_neighbour_indices)| Node {
+                concept,
+                lower_neighbour_indices,
+            })
+            .collect();
+
+        Self::new(nodes)
+    }
+
+    /// Lattice of the given nodes, indexed by their intents and extents
+    fn new(nodes: Vec<Node>) -> Self {
+        let mut intents = HashMap::with_capacity(nodes.len());
+        let mut extents = HashMap::with_capacity(nodes.len());
+
+        for (index, node) in nodes.iter().enumerate() {
+            intents.insert(sorted(&node.concept.intents), index);
+            extents.insert(sorted(&node.concept.extents), index);
+        }
+
+        Self {
+            nodes,
+            intents,
+            extents,
+        }
+    }
+
+    /// Number of concepts in the lattice
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Number of edges in the Hasse diagram
+    pub fn edge_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|n| n.lower_neighbour_indices.len())
+            .sum()
+    }
+
+    /// All concepts, where the position of a concept is its node index
+    pub fn concepts(&self) -> impl Iterator<Item = &Concept> {
+        self.nodes.iter().map(|n| &n.concept)
+    }
+
+    pub fn concept(&self, index: usize) -> Option<&Concept> {
+        self.nodes.get(index).map(|n| &n.concept)
+    }
+
+    /// Index of the concept having all objects
+    pub fn top(&self) -> Option<usize> {
+        (0..self.nodes.len()).max_by_key(|&i| self.nodes[i].concept.extents.len())
+    }
+
+    /// Index of the concept having all attributes
+    pub fn bottom(&self) -> Option<usize> {
+        (0..self.nodes.len()).max_by_key(|&i| self.nodes[i].concept.intents.len())
+    }
+
+    /// Indices of the concepts directly below the concept at `index`
+    pub fn lower_neighbours(&self, index: usize) -> Option<&[usize]> {
+        self.nodes
+            .get(index)
+            .map(|n| n.lower_neighbour_indices.as_slice())
+    }
+
+    /// Indices of the concepts directly above the concept at `index`
+    pub fn upper_neighbours(&self, index: usize) -> Option<Vec<usize>> {
+        self.nodes.get(index)?;
+
+        let upper = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.lower_neighbour_indices.contains(&index))
+            .map(|(i, _)| i)
+            .collect();
+
+        Some(upper)
+    }
+
+    /// Index of the concept with exactly these intents, in any order
+    pub fn find_by_intents(&self, intents: &[String]) -> Option<usize> {
+        self.intents.get(&sorted(intents)).copied()
+    }
+
+    /// Index of the concept with exactly these extents, in any order
+    pub fn find_by_extents(&self, extents: &[String]) -> Option<usize> {
+        self.extents.get(&sorted(extents)).copied()
+    }
+
+    /// Index of the infimum of the given concepts, found by the intersection of their extents.
+    /// An empty `indices` returns the top, and an unknown index `None`.
+    pub fn meet(&self, indices: &[usize]) -> Option<usize> {
+        match self.common(indices, |c| &c.extents)? {
+            Some(extents) => self.find_by_extents(&extents),
+            None => self.top(),
+        }
+    }
+
+    /// Index of the supremum of the given concepts, found by the intersection of their intents.
+    /// An empty `indices` returns the bottom, and an unknown index `None`.
+    pub fn join(&self, indices: &[usize]) -> Option<usize> {
+        match self.common(indices, |c| &c.intents)? {
+            Some(intents) => self.find_by_intents(&intents),
+            None => self.bottom(),
+        }
+    }
+
+    /// Index of the object concept γ(g): the smallest concept having `object` in its extents
+    pub fn object_concept(&self, object: &str) -> Option<usize> {
+        (0..self.nodes.len())
+            .filter(|&i| self.nodes[i].concept.extents.iter().any(|o| o == object))
+            .min_by_key(|&i| self.nodes[i].concept.extents.len())
+    }
+
+    /// Index of the attribute concept μ(m): the largest concept having `attribute` in its
+    /// intents
+    pub fn attribute_concept(&self, attribute: &str) -> Option<usize> {
+        (0..self.nodes.len())
+            .filter(|&i| self.nodes[i].concept.intents.iter().any(|a| a == attribute))
+            .min_by_key(|&i| self.nodes[i].concept.intents.len())
+    }
+
+    /// Objects whose object concept is the concept at `index`, being the objects that are in
+    /// none of its lower neighbours
+    pub fn own_objects(&self, index: usize) -> Option<Vec<&str>> {
+        let node = self.nodes.get(index)?;
+
+        let own = node
+            .concept
+            .extents
+            .iter()
+            .filter(|o| {
+                node.lower_neighbour_indices
+                    .iter()
+                    .all(|&l| !self.nodes[l].concept.extents.contains(o))
+            })
+            .map(String::as_str)
+            .collect();
+
+        Some(own)
+    }
+
+    /// Attributes whose attribute concept is the concept at `index`, being the attributes
+    /// that are in none of its upper neighbours
+    pub fn own_attributes(&self, index: usize) -> Option<Vec<&str>> {
+        let upper = self.upper_neighbours(index)?;
+
+        Some(self.own_attributes_below(index, &upper))
+    }
+
+    /// [Lattice::own_attributes] given the upper neighbours of the concept at `index`
+    fn own_attributes_below(&self, index: usize, upper: &[usize]) -> Vec<&str> {
+        self.nodes[index]
+            .concept
+            .intents
+            .iter()
+            .filter(|a| {
+                upper
+                    .iter()
+                    .all(|&u| !self.nodes[u].concept.intents.contains(a))
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Upper neighbours of every node, inverting the lower neighbours in a single pass
+    fn upper_neighbour_lists(&self) -> Vec<Vec<usize>> {
+        let mut upper = vec![Vec::new(); self.nodes.len()];
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            for &lower in &node.lower_neighbour_indices {
+                upper[lower].push(index);
+            }
+        }
+
+        upper
+    }
+
+    /// Intersection of a side of the given concepts, or `None` on an unknown index
+    fn common<F>(&self, indices: &[usize], side: F) -> Option<Option<Vec<String>>>
+    where
+        F: Fn(&Concept) -> &Vec<String>,
+    {
+        let mut common: Option<Vec<String>> = None;
+
+        for &index in indices {
+            let set = side(self.concept(index)?);
+
+            common = Some(match common {
+                Some(mut common) => {
+                    let set: HashSet<&String> = set.iter().collect();
+                    common.retain(|x| set.contains(x));
+                    common
+                }
+                None => set.clone(),
+            });
+        }
+
+        Some(common)
+    }
+}
+
+fn sorted(names: &[String]) -> Vec<String> {
+    let mut names = names.to_vec();
+    names.sort_unstable();
+
+    names
 }
 
 impl From<Vec<Concept>> for Lattice {
@@ -48,7 +285,7 @@ impl From<Vec<Concept>> for Lattice {
             nodes.push(node);
         }
 
-        Self { nodes }
+        Self::new(nodes)
     }
 }
 
@@ -58,9 +295,8 @@ mod tests {
 
     use super::{Lattice, Node};
 
-    #[test]
-    fn from_concepts() {
-        let input = vec![
+    fn triangles() -> Vec<Concept> {
+        vec![
             Concept {
                 intents: vec!["d".to_string()],
                 extents: vec!["1".to_string(), "5".to_string()],
@@ -120,7 +356,12 @@ mod tests {
                 intents: vec!["b".to_string(), "c".to_string()],
                 extents: vec!["4".to_string(), "6".to_string()],
             },
-        ];
+        ]
+    }
+
+    #[test]
+    fn from_concepts() {
+        let input = triangles();
 
         let actual = Lattice::from(input);
         let nodes = vec![
@@ -215,8 +456,124 @@ mod tests {
             },
         ];
 
-        let expected = Lattice { nodes };
+        let expected = Lattice::new(nodes);
 
         assert_eq!(actual, expected);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let lattice = Lattice::from(vec![
+            Concept {
+                intents: Vec::new(),
+                extents: vec!["1".to_string(), "2".to_string()],
+            },
+            Concept {
+                intents: vec!["a".to_string()],
+                extents: vec!["1".to_string()],
+            },
+        ]);
+
+        let json = serde_json::to_string(&lattice).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"nodes":[{"concept":{"extents":["1"],"intents":["a"]},"lower_neighbour_indices":[]},{"concept":{"extents":["1","2"],"intents":[]},"lower_neighbour_indices":[0]}]}"#
+        );
+        assert_eq!(serde_json::from_str::<Lattice>(&json).unwrap(), lattice);
+    }
+
+    #[test]
+    fn queries() {
+        let lattice = Lattice::from(triangles());
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(lattice.len(), 10);
+        assert_eq!(lattice.edge_count(), 14);
+        assert_eq!(lattice.concepts().count(), 10);
+        assert_eq!(lattice.top(), Some(9));
+        assert_eq!(lattice.bottom(), Some(0));
+        assert_eq!(lattice.concept(9).unwrap().intents, Vec::<String>::new());
+        assert_eq!(lattice.concept(10), None);
+
+        let b = lattice.find_by_intents(&names(&["b"])).unwrap();
+
+        assert_eq!(b, 8);
+        assert_eq!(
+            lattice.find_by_extents(&names(&["6", "4", "2", "1"])),
+            Some(b)
+        );
+        assert_eq!(lattice.lower_neighbours(b), Some(&[2, 3, 4][..]));
+        assert_eq!(lattice.upper_neighbours(b), Some(vec![9]));
+        assert_eq!(lattice.upper_neighbours(0), Some(vec![1, 2, 3]));
+        assert_eq!(lattice.upper_neighbours(10), None);
+        assert_eq!(lattice.find_by_intents(&names(&["a"])), None);
+    }
+
+    #[test]
+    fn meet_join() {
+        let lattice = Lattice::from(triangles());
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let b = lattice.find_by_intents(&names(&["b"])).unwrap();
+        let c = lattice.find_by_intents(&names(&["c"])).unwrap();
+        let d = lattice.find_by_intents(&names(&["d"])).unwrap();
+        let e = lattice.find_by_intents(&names(&["e"])).unwrap();
+
+        assert_eq!(
+            lattice.meet(&[b, c]),
+            lattice.find_by_intents(&names(&["b", "c"]))
+        );
+        assert_eq!(
+            lattice.meet(&[c, d, e]),
+            lattice.find_by_extents(&names(&[]))
+        );
+        assert_eq!(lattice.join(&[b, c]), lattice.top());
+        assert_eq!(lattice.join(&[0, d]), Some(d));
+        assert_eq!(
+            lattice.join(&[
+                lattice.find_by_intents(&names(&["b", "e"])).unwrap(),
+                lattice.find_by_intents(&names(&["b", "d"])).unwrap(),
+            ]),
+            Some(b)
+        );
+        assert_eq!(lattice.meet(&[b]), Some(b));
+        assert_eq!(lattice.meet(&[]), lattice.top());
+        assert_eq!(lattice.join(&[]), lattice.bottom());
+        assert_eq!(lattice.meet(&[b, 10]), None);
+    }
+
+    #[test]
+    fn object_attribute_concepts() {
+        let lattice = Lattice::from(triangles());
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let index = |intents: &[&str]| lattice.find_by_intents(&names(intents));
+
+        assert_eq!(lattice.object_concept("4"), index(&["a", "b", "c"]));
+        assert_eq!(lattice.object_concept("6"), index(&["b", "c"]));
+        assert_eq!(lattice.object_concept("3"), index(&["c"]));
+        assert_eq!(lattice.object_concept("missing"), None);
+        assert_eq!(lattice.attribute_concept("a"), index(&["a", "b", "c"]));
+        assert_eq!(lattice.attribute_concept("b"), index(&["b"]));
+        assert_eq!(lattice.attribute_concept("missing"), None);
+
+        let bc = index(&["b", "c"]).unwrap();
+        let c = index(&["c"]).unwrap();
+
+        assert_eq!(lattice.own_objects(bc), Some(vec!["6"]));
+        assert_eq!(lattice.own_attributes(bc), Some(Vec::new()));
+        assert_eq!(lattice.own_objects(c), Some(vec!["3"]));
+        assert_eq!(lattice.own_attributes(c), Some(vec!["c"]));
+        assert_eq!(
+            lattice.own_objects(lattice.bottom().unwrap()),
+            Some(Vec::new())
+        );
+        assert_eq!(lattice.own_objects(10), None);
+
+        for object in ["1", "2", "3", "4", "5", "6", "7"] {
+            let concept = lattice.object_concept(object).unwrap();
+
+            assert!(lattice.own_objects(concept).unwrap().contains(&object));
+        }
+    }
 }
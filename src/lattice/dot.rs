@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use super::Lattice;
+
+/// How concepts are labelled when drawing a [Lattice]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Labels {
+    /// Every concept shows all of its intents and extents
+    Full,
+    /// Objects only show at their object concept and attributes only at their attribute
+    /// concept, which keeps large diagrams readable
+    #[default]
+    Reduced,
+}
+
+impl Lattice {
+    /// Render the Hasse diagram in Graphviz DOT, with the top concept at the top
+    pub fn to_dot(&self, labels: Labels) -> String {
+        let mut output =
+            String::from("digraph lattice {\n  node [shape=box];\n  edge [dir=none];\n");
+
+        let upper = match labels {
+            Labels::Full => Vec::new(),
+            Labels::Reduced => self.upper_neighbour_lists(),
+        };
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let (intents, extents) = match labels {
+                Labels::Full => (
+                    node.concept.intents.iter().map(String::as_str).collect(),
+                    node.concept.extents.iter().map(String::as_str).collect(),
+                ),
+                Labels::Reduced => (
+                    self.own_attributes_below(index, &upper[index]),
+                    self.own_objects(index).unwrap_or_default(),
+                ),
+            };
+
+            writeln!(
+                output,
+                "  {} [label=\"{}\\n{}\"];",
+                index,
+                escape(&intents.join(", ")),
+                escape(&extents.join(", "))
+            )
+            .unwrap();
+        }
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            for lower in &node.lower_neighbour_indices {
+                writeln!(output, "  {} -> {};", index, lower).unwrap();
+            }
+        }
+
+        output.push_str("}\n");
+
+        output
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Context, Labels};
+
+    fn context() -> Context {
+        Context::from_csv(
+            r#",running,artificial,"small ""pond"""
+                pond,,X,X
+                river, x ,,
+                canal,X,X,"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn to_dot_full() {
+        let actual = context().lattice().to_dot(Labels::Full);
+        let expected = r#"digraph lattice {
+  node [shape=box];
+  edge [dir=none];
+  0 [label="running, artificial, small \"pond\"\n"];
+  1 [label="running, artificial\ncanal"];
+  2 [label="artificial, small \"pond\"\npond"];
+  3 [label="running\nriver, canal"];
+  4 [label="artificial\npond, canal"];
+  5 [label="\npond, river, canal"];
+  1 -> 0;
+  2 -> 0;
+  3 -> 1;
+  4 -> 1;
+  4 -> 2;
+  5 -> 3;
+  5 -> 4;
+}
+"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn to_dot_reduced() {
+        let actual = context().lattice().to_dot(Labels::Reduced);
+        let expected = r#"digraph lattice {
+  node [shape=box];
+  edge [dir=none];
+  0 [label="\n"];
+  1 [label="\ncanal"];
+  2 [label="small \"pond\"\npond"];
+  3 [label="running\nriver"];
+  4 [label="artificial\n"];
+  5 [label="\n"];
+  1 -> 0;
+  2 -> 0;
+  3 -> 1;
+  4 -> 1;
+  4 -> 2;
+  5 -> 3;
+  5 -> 4;
+}
+"#;
+
+        assert_eq!(actual, expected);
+    }
+}
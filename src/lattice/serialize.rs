@@ -0,0 +1,66 @@
+use anyhow::{bail, Result};
+use serde::{de::Error, Deserialize, Deserializer};
+
+use super::{Lattice, Node};
+
+/// Serialised form of a [Lattice], checked before use
+#[derive(Deserialize)]
+struct LatticeData {
+    nodes: Vec<Node>,
+}
+
+impl<'de> Deserialize<'de> for Lattice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = LatticeData::deserialize(deserializer)?;
+
+        Lattice::try_from(data).map_err(D::Error::custom)
+    }
+}
+
+impl TryFrom<LatticeData> for Lattice {
+    type Error = anyhow::Error;
+
+    fn try_from(data: LatticeData) -> Result<Self> {
+        for (index, node) in data.nodes.iter().enumerate() {
+            if let Some(&lower) = node.lower_neighbour_indices.iter().find(|&&l| l >= index) {
+                bail!(
+                    "lower neighbour {} of node {} should come before it",
+                    lower,
+                    index
+                );
+            }
+        }
+
+        Ok(Lattice::new(data.nodes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Lattice;
+
+    #[test]
+    fn invalid_neighbours() {
+        let node = |lower: &str| {
+            format!(
+                r#"{{"concept":{{"extents":[],"intents":[]}},"lower_neighbour_indices":[{}]}}"#,
+                lower
+            )
+        };
+
+        for nodes in [node("5"), format!("{},{}", node(""), node("1"))] {
+            let json = format!(r#"{{"nodes":[{}]}}"#, nodes);
+            let error = serde_json::from_str::<Lattice>(&json).unwrap_err();
+
+            assert!(
+                error.to_string().contains("should come before it"),
+                "{}",
+                error
+            );
+        }
+
+        let json = format!(r#"{{"nodes":[{},{}]}}"#, node(""), node("0"));
+
+        assert_eq!(serde_json::from_str::<Lattice>(&json).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,297 @@
+use std::{collections::HashMap, fmt::Write};
+
+use super::Lattice;
+
+/// Distance between neighbouring nodes and between levels in the line diagram
+const SPACING: f64 = 80.0;
+/// Space around the diagram for labels
+const MARGIN: f64 = 60.0;
+const RADIUS: f64 = 6.0;
+/// Number of barycentre sweeps used to reduce edge crossings
+const SWEEPS: usize = 8;
+
+impl Lattice {
+    /// Position of every node in a layered line diagram with the top concept at the top.
+    ///
+    /// Concepts are ranked by the size of their intents, after which the order within each
+    /// level is improved by repeatedly moving nodes to the barycentre of their neighbours in
+    /// the level above and below. Horizontal positions are then additive: every attribute
+    /// gets a step derived from that order, each concept is placed at the sum of the steps of
+    /// its intent, and nodes sharing a level are pushed apart where they would overlap.
+    pub fn layout(&self) -> Vec<(f64, f64)> {
+        let sizes: Vec<usize> = self.nodes.iter().map(|n| n.concept.intents.len()).collect();
+        let mut distinct = sizes.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        let ranks: Vec<usize> = sizes
+            .iter()
+            .map(|s| distinct.binary_search(s).unwrap())
+            .collect();
+        let mut levels: Vec<Vec<usize>> = vec![Vec::new(); distinct.len()];
+        for (index, &rank) in ranks.iter().enumerate() {
+            levels[rank].push(index);
+        }
+
+        let upper = self.upper_neighbour_lists();
+        let mut position: Vec<f64> = vec![0.0; self.nodes.len()];
+        let place = |levels: &[Vec<usize>], position: &mut Vec<f64>| {
+            for level in levels {
+                for (i, &node) in level.iter().enumerate() {
+                    position[node] = i as f64 - (level.len() as f64 - 1.0) / 2.0;
+                }
+            }
+        };
+
+        place(&levels, &mut position);
+
+        for sweep in 0..SWEEPS {
+            let downwards = sweep % 2 == 0;
+            let order: Vec<usize> = if downwards {
+                (1..levels.len()).collect()
+            } else {
+                (0..levels.len().saturating_sub(1)).rev().collect()
+            };
+
+            for rank in order {
+                let barycentre = |node: usize| {
+                    let neighbours = if downwards {
+                        &upper[node]
+                    } else {
+                        &self.nodes[node].lower_neighbour_indices
+                    };
+
+                    if neighbours.is_empty() {
+                        position[node]
+                    } else {
+                        neighbours.iter().map(|&n| position[n]).sum::<f64>()
+                            / neighbours.len() as f64
+                    }
+                };
+
+                let mut keyed: Vec<(f64, usize)> =
+                    levels[rank].iter().map(|&n| (barycentre(n), n)).collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                levels[rank] = keyed.into_iter().map(|(_, n)| n).collect();
+
+                place(&levels[rank..=rank], &mut position);
+            }
+        }
+
+        // Additive placement: every attribute contributes the horizontal step from the upper
+        // neighbours of its attribute concept to that concept, and every concept sits at the
+        // sum of the steps of its intent
+        let mut steps: HashMap<&str, f64> = HashMap::new();
+
+        for index in 0..self.nodes.len() {
+            let own = self.own_attributes_below(index, &upper[index]);
+            let above = if upper[index].is_empty() {
+                0.0
+            } else {
+                upper[index].iter().map(|&u| position[u]).sum::<f64>() / upper[index].len() as f64
+            };
+
+            for attribute in &own {
+                steps.insert(attribute, (position[index] - above) / own.len() as f64);
+            }
+        }
+
+        let additive: Vec<f64> = self
+            .nodes
+            .iter()
+            .map(|n| n.concept.intents.iter().map(|a| steps[a.as_str()]).sum())
+            .collect();
+
+        // Keep the additive order within each level, breaking ties by the barycentre order,
+        // and push apart nodes closer than the spacing
+        let mut x = vec![0.0; self.nodes.len()];
+
+        for level in &levels {
+            let mut level: Vec<(usize, usize)> = level.iter().copied().enumerate().collect();
+            level.sort_by(|a, b| additive[a.1].total_cmp(&additive[b.1]).then(a.0.cmp(&b.0)));
+
+            let mut previous = f64::NEG_INFINITY;
+            for (_, node) in level {
+                x[node] = additive[node].max(previous + 1.0);
+                previous = x[node];
+            }
+        }
+
+        let left = x.iter().copied().fold(f64::INFINITY, f64::min);
+
+        (0..self.nodes.len())
+            .map(|i| {
+                (
+                    MARGIN + (x[i] - left) * SPACING,
+                    MARGIN + ranks[i] as f64 * SPACING,
+                )
+            })
+            .collect()
+    }
+
+    /// Render a standalone SVG line diagram with reduced labels: attributes above the node of
+    /// their attribute concept and objects below the node of their object concept
+    pub fn to_svg(&self) -> String {
+        let layout = self.layout();
+        let upper = self.upper_neighbour_lists();
+        let width = layout.iter().map(|p| p.0).fold(0.0, f64::max) + MARGIN;
+        let height = layout.iter().map(|p| p.1).fold(0.0, f64::max) + MARGIN;
+
+        let mut output = String::new();
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="sans-serif" font-size="12">"#,
+            width, height, width, height
+        )
+        .unwrap();
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let (x1, y1) = layout[index];
+
+            for &lower in &node.lower_neighbour_indices {
+                let (x2, y2) = layout[lower];
+                writeln!(
+                    output,
+                    r#"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" />"#,
+                    x1, y1, x2, y2
+                )
+                .unwrap();
+            }
+        }
+
+        for (index, &(x, y)) in layout.iter().enumerate() {
+            writeln!(
+                output,
+                r#"  <circle cx="{}" cy="{}" r="{}" fill="white" stroke="black" />"#,
+                x, y, RADIUS
+            )
+            .unwrap();
+
+            let attributes = self.own_attributes_below(index, &upper[index]);
+            if !attributes.is_empty() {
+                writeln!(
+                    output,
+                    r#"  <text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                    x,
+                    y - RADIUS - 4.0,
+                    escape(&attributes.join(", "))
+                )
+                .unwrap();
+            }
+
+            let objects = self.own_objects(index).unwrap_or_default();
+            if !objects.is_empty() {
+                writeln!(
+                    output,
+                    r#"  <text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                    x,
+                    y + RADIUS + 14.0,
+                    escape(&objects.join(", "))
+                )
+                .unwrap();
+            }
+        }
+
+        output.push_str("</svg>\n");
+
+        output
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Context;
+
+    fn context() -> Context {
+        Context::from_csv(
+            r#",running,artificial,small & still
+                pond,,X,X
+                river, x ,,
+                canal,X,X,"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn layout() {
+        let lattice = context().lattice();
+        let layout = lattice.layout();
+
+        let top = layout[lattice.top().unwrap()];
+        let bottom = layout[lattice.bottom().unwrap()];
+
+        assert_eq!(layout.len(), lattice.len());
+        assert!(top.1 < bottom.1, "top should be drawn above the bottom");
+
+        for (index, position) in layout.iter().enumerate() {
+            for &lower in lattice.lower_neighbours(index).unwrap() {
+                assert!(position.1 < layout[lower].1, "edges should point down");
+            }
+
+            for other in &layout[index + 1..] {
+                assert_ne!(position, other, "nodes should not overlap");
+            }
+        }
+    }
+
+    #[test]
+    fn layout_additive() {
+        let lattice = context().lattice();
+        let layout = lattice.layout();
+        let x = |intents: &[&str]| {
+            let intents: Vec<_> = intents.iter().map(|i| i.to_string()).collect();
+            layout[lattice.find_by_intents(&intents).unwrap()].0
+        };
+
+        // Each attribute moves a concept by the same amount wherever it is added
+        let running = x(&["running"]) - x(&[]);
+        let artificial = x(&["artificial"]) - x(&[]);
+
+        assert!(running != 0.0 && artificial != 0.0);
+        assert!((x(&["running", "artificial"]) - x(&[]) - running - artificial).abs() < 1e-9);
+    }
+
+    #[test]
+    fn layout_crossings() {
+        // Without reordering the level with `a` and `b` the edges to `ac` and `bd` would cross
+        let context = Context::from_csv(
+            r#",b,a,d,c
+                1,x, ,x,
+                2, ,x, ,x
+                3,x,x, ,"#,
+        )
+        .unwrap();
+        let lattice = context.lattice();
+        let layout = lattice.layout();
+        let x = |intents: &[&str]| {
+            let intents: Vec<_> = intents.iter().map(|i| i.to_string()).collect();
+            layout[lattice.find_by_intents(&intents).unwrap()].0
+        };
+
+        assert_eq!(x(&["b"]) < x(&["a"]), x(&["b", "d"]) < x(&["a", "c"]));
+    }
+
+    #[test]
+    fn to_svg() {
+        let lattice = context().lattice();
+        let svg = lattice.to_svg();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<circle").count(), lattice.len());
+        assert_eq!(svg.matches("<line").count(), lattice.edge_count());
+        assert!(svg.contains(">small &amp; still</text>"));
+        assert!(svg.contains(">pond</text>"));
+        assert!(
+            !svg.contains(">pond, canal</text>"),
+            "labels should be reduced"
+        );
+    }
+}
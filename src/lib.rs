@@ -1,15 +1,20 @@
-#![feature(drain_filter)]
-
+mod bitset;
 mod concept;
 mod context;
-mod implications;
+pub mod implications;
 mod lattice;
+mod many_valued;
+mod names;
 
 pub use concept::Concept;
-pub use context::Context;
-pub use lattice::Lattice;
+pub use context::{Algorithm, Arrow, CexSections, Concepts, Context, CsvOptions, Reduction};
+pub use implications::{Conclusion, Implication};
+pub use lattice::{Labels, Lattice};
+pub use many_valued::{ManyValuedContext, Scale, Value};
+
+use bitset::BitSet;
 
-pub fn next_closure<'a, F>(set: &[String], subset: &[String], closure: F) -> Option<Vec<String>>
+pub fn next_closure<F>(set: &[String], subset: &[String], closure: F) -> Option<Vec<String>>
 where
     F: Fn(&[String]) -> Option<Vec<String>>,
 {
@@ -37,6 +42,35 @@ where
     None
 }
 
+/// Index based [next_closure] where the lectic order is the index order
+pub(crate) fn next_closure_set<F>(subset: &BitSet, closure: F) -> Option<BitSet>
+where
+    F: Fn(&BitSet) -> BitSet,
+{
+    let mut subset = subset.clone();
+
+    for m in (0..subset.len()).rev() {
+        if subset.contains(m) {
+            subset.remove(m);
+            continue;
+        }
+
+        subset.insert(m);
+
+        let next = closure(&subset);
+
+        // Only valid if nothing before `m` was added by the closure
+        if next.equal_below(&subset, m) {
+            return Some(next);
+        }
+
+        // Remove from subset again since the add was only to test
+        subset.remove(m);
+    }
+
+    None
+}
+
 fn lexical_m(m: &[String], a: &[String], b: &[String]) -> usize {
     let a = a.to_vec();
     let b = b.to_vec();
@@ -52,7 +86,15 @@ fn lexical_m(m: &[String], a: &[String], b: &[String]) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use super::{lexical_m, next_closure};
+    use crate::bitset::BitSet;
+
+    use super::{lexical_m, next_closure, next_closure_set};
+
+    fn set(len: usize, indices: &[usize]) -> BitSet {
+        let mut set = BitSet::new(len);
+        indices.iter().for_each(|&i| set.insert(i));
+        set
+    }
 
     #[test]
     fn lexical_m_ordered() {
@@ -161,4 +203,33 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn next_closure_set_jump() {
+        // 0 = running, 1 = artificial, 2 = small
+        let actual = next_closure_set(&set(3, &[]), |n| match n.ones().collect::<Vec<_>>()[..] {
+            [2] => set(3, &[1, 2]),
+            [1] => set(3, &[1]),
+            _ => panic!("unexpected input: {:?}", n),
+        });
+
+        assert_eq!(actual, Some(set(3, &[1])));
+    }
+
+    #[test]
+    fn next_closure_set_double() {
+        let actual = next_closure_set(&set(3, &[1]), |n| match n.ones().collect::<Vec<_>>()[..] {
+            [1, 2] => set(3, &[1, 2]),
+            _ => panic!("unexpected input: {:?}", n),
+        });
+
+        assert_eq!(actual, Some(set(3, &[1, 2])));
+    }
+
+    #[test]
+    fn next_closure_set_end() {
+        let actual = next_closure_set(&set(3, &[0, 1, 2]), |n| panic!("unexpected input: {:?}", n));
+
+        assert_eq!(actual, None);
+    }
 }
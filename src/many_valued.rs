@@ -0,0 +1,425 @@
+use anyhow::{anyhow, bail, Result};
+use csv::Reader;
+use std::{collections::HashSet, fmt, io::Read};
+
+use crate::{bitset::BitSet, names::Names, Context};
+
+/// Value of an object for a many-valued attribute
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl From<&str> for Value {
+    /// Finite numbers are parsed as such, anything else, including `NaN` and `inf`, is kept
+    /// as text
+    fn from(value: &str) -> Self {
+        value
+            .parse()
+            .ok()
+            .filter(|n: &f64| n.is_finite())
+            .map_or_else(|| Value::Text(value.to_string()), Value::Number)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Text(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+/// Conceptual scale turning one many-valued attribute into binary attributes
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scale {
+    /// An `m=v` attribute for every value `v` occurring for `m`
+    Nominal,
+    /// An `m<=t` attribute for every threshold `t`
+    Ordinal(Vec<f64>),
+    /// Both an `m<=t` and an `m>=t` attribute for every threshold `t`
+    Interordinal(Vec<f64>),
+    /// `m<=t` attributes for the `lower` thresholds and `m>=t` attributes for the `upper` ones
+    Biordinal { lower: Vec<f64>, upper: Vec<f64> },
+}
+
+/// Context where every object has a [Value], or none, for each attribute
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct ManyValuedContext {
+    objects: Names,
+    attributes: Names,
+    /// Original text and parsed value of every cell, by object and then attribute
+    values: Vec<Vec<Option<(String, Value)>>>,
+}
+
+impl ManyValuedContext {
+    /// Empty context over the given attributes, failing on a repeated attribute name
+    pub fn new(attributes: Vec<String>) -> Result<Self> {
+        let mut seen = HashSet::new();
+
+        if let Some(attribute) = attributes.iter().find(|a| !seen.insert(*a)) {
+            bail!("attribute '{}' is repeated", attribute);
+        }
+
+        Ok(Self {
+            objects: Names::default(),
+            attributes: attributes.into_iter().collect(),
+            values: Vec::new(),
+        })
+    }
+
+    pub fn objects(&self) -> &[String] {
+        self.objects.as_slice()
+    }
+
+    pub fn attributes(&self) -> &[String] {
+        self.attributes.as_slice()
+    }
+
+    /// Read a CSV with attribute names in the first row and object names in the first column.
+    /// Empty cells have no value.
+    pub fn from_csv(data: &str) -> Result<Self> {
+        Self::from_csv_reader(data.as_bytes())
+    }
+
+    /// Stream a CSV in the format of [ManyValuedContext::from_csv]
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self> {
+        let mut reader = Reader::from_reader(reader);
+
+        let mut headers = reader.headers()?.iter();
+        // First column is for objects
+        headers.next();
+        let mut context = Self::new(headers.map(|h| h.trim().to_string()).collect())?;
+
+        for record in reader.into_records() {
+            let record = record?;
+
+            let mut iter = record.iter().map(str::trim);
+            let name = iter.next().unwrap_or_default().to_string();
+            let values = iter
+                .map(|v| (!v.is_empty()).then(|| (v.to_string(), Value::from(v))))
+                .collect();
+
+            context.push_object(name, values)?;
+        }
+
+        Ok(context)
+    }
+
+    /// Append an object, failing if its name is already taken or the number of values does
+    /// not match the attributes
+    pub fn add_object(&mut self, name: String, values: Vec<Option<Value>>) -> Result<()> {
+        let values = values
+            .into_iter()
+            .map(|v| v.map(|v| (v.to_string(), v)))
+            .collect();
+
+        self.push_object(name, values)
+    }
+
+    fn push_object(&mut self, name: String, values: Vec<Option<(String, Value)>>) -> Result<()> {
+        if self.objects.index(&name).is_some() {
+            bail!("object '{}' already exists", name);
+        }
+
+        if values.len() != self.attributes.len() {
+            bail!(
+                "object '{}' has {} values for {} attributes",
+                name,
+                values.len(),
+                self.attributes.len()
+            );
+        }
+
+        self.objects.push(name);
+        self.values.push(values);
+
+        Ok(())
+    }
+
+    pub fn value(&self, object: &str, attribute: &str) -> Option<&Value> {
+        let object_index = self.objects.index(object)?;
+        let attribute_index = self.attributes.index(attribute)?;
+
+        self.values[object_index][attribute_index]
+            .as_ref()
+            .map(|(_, value)| value)
+    }
+
+    /// Derive a binary [Context] by applying each scale to its attribute, in the given order.
+    /// Attributes without a scale are left out.
+    pub fn scale(&self, scales: &[(String, Scale)]) -> Result<Context> {
+        let mut attributes = Vec::new();
+        let mut seen = HashSet::new();
+        // Incidence of every generated attribute over the objects
+        let mut columns = Vec::new();
+
+        for (attribute, scale) in scales {
+            let index = self
+                .attributes
+                .index(attribute)
+                .ok_or_else(|| anyhow!("unknown attribute '{}'", attribute))?;
+            let values: Vec<_> = self
+                .values
+                .iter()
+                .map(|v| {
+                    v[index]
+                        .as_ref()
+                        .map(|(text, value)| (text.as_str(), value))
+                })
+                .collect();
+
+            for (name, column) in scale_attribute(attribute, &values, scale)? {
+                if !seen.insert(name.clone()) {
+                    bail!("scaling generates attribute '{}' more than once", name);
+                }
+
+                attributes.push(name);
+                columns.push(column);
+            }
+        }
+
+        let mut context = Context::with_attributes(attributes.into_iter().collect::<Names>());
+
+        for (o, name) in self.objects.as_slice().iter().enumerate() {
+            let mut row = BitSet::new(columns.len());
+
+            for (a, column) in columns.iter().enumerate() {
+                if column[o] {
+                    row.insert(a);
+                }
+            }
+
+            context.push_object(Some(name.to_string()), row);
+        }
+
+        Ok(context)
+    }
+}
+
+/// Generated attribute names with, for every object, whether it has that attribute.
+/// Values are given with their original text, which nominal scales compare.
+fn scale_attribute(
+    attribute: &str,
+    values: &[Option<(&str, &Value)>],
+    scale: &Scale,
+) -> Result<Vec<(String, Vec<bool>)>> {
+    let numbers = || -> Result<Vec<Option<f64>>> {
+        values
+            .iter()
+            .map(|v| match v.map(|(_, value)| value) {
+                Some(Value::Number(n)) => Ok(Some(*n)),
+                Some(Value::Text(t)) => bail!(
+                    "attribute '{}' has non-numeric value '{}' for an ordered scale",
+                    attribute,
+                    t
+                ),
+                None => Ok(None),
+            })
+            .collect()
+    };
+    let at_most = |numbers: &[Option<f64>], t: f64| {
+        let column = numbers.iter().map(|n| n.is_some_and(|n| n <= t)).collect();
+        (format!("{}<={}", attribute, t), column)
+    };
+    let at_least = |numbers: &[Option<f64>], t: f64| {
+        let column = numbers.iter().map(|n| n.is_some_and(|n| n >= t)).collect();
+        (format!("{}>={}", attribute, t), column)
+    };
+
+    let scaled = match scale {
+        Scale::Nominal => {
+            let mut distinct: Vec<&str> = Vec::new();
+
+            for &(text, _) in values.iter().flatten() {
+                if !distinct.contains(&text) {
+                    distinct.push(text);
+                }
+            }
+
+            distinct
+                .into_iter()
+                .map(|d| {
+                    let column = values
+                        .iter()
+                        .map(|v| v.map(|(t, _)| t) == Some(d))
+                        .collect();
+                    (format!("{}={}", attribute, d), column)
+                })
+                .collect()
+        }
+        Scale::Ordinal(thresholds) => {
+            let numbers = numbers()?;
+            thresholds.iter().map(|&t| at_most(&numbers, t)).collect()
+        }
+        Scale::Interordinal(thresholds) => {
+            let numbers = numbers()?;
+            let lower = thresholds.iter().map(|&t| at_most(&numbers, t));
+            let upper = thresholds.iter().map(|&t| at_least(&numbers, t));
+
+            lower.chain(upper).collect()
+        }
+        Scale::Biordinal { lower, upper } => {
+            let numbers = numbers()?;
+            let lower = lower.iter().map(|&t| at_most(&numbers, t));
+            let upper = upper.iter().map(|&t| at_least(&numbers, t));
+
+            lower.chain(upper).collect()
+        }
+    };
+
+    Ok(scaled)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Context;
+
+    use super::{ManyValuedContext, Scale, Value};
+
+    fn people() -> ManyValuedContext {
+        ManyValuedContext::from_csv(
+            r#",age,colour,height
+                ann, 25,   red,  170
+                bob, 40,  blue,
+                cat, 30,   red,  185"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn from_csv() {
+        let context = people();
+
+        assert_eq!(context.value("ann", "age"), Some(&Value::Number(25.0)));
+        assert_eq!(
+            context.value("bob", "colour"),
+            Some(&Value::Text("blue".to_string()))
+        );
+        assert_eq!(context.value("bob", "height"), None);
+    }
+
+    #[test]
+    fn scale() {
+        let context = people();
+
+        let actual = context
+            .scale(&[
+                ("colour".to_string(), Scale::Nominal),
+                ("age".to_string(), Scale::Ordinal(vec![30.0])),
+                ("height".to_string(), Scale::Interordinal(vec![175.0])),
+            ])
+            .unwrap();
+        let expected = Context::from_csv(
+            r#",colour=red,colour=blue,age<=30,height<=175,height>=175
+                ann,  X   ,           ,   X   ,     X     ,
+                bob,      ,     X     ,       ,           ,
+                cat,  X   ,           ,   X   ,           ,     X"#,
+        )
+        .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn scale_biordinal() {
+        let context = people();
+
+        let actual = context
+            .scale(&[(
+                "age".to_string(),
+                Scale::Biordinal {
+                    lower: vec![25.0],
+                    upper: vec![35.0],
+                },
+            )])
+            .unwrap();
+        let expected = Context::from_csv(
+            r#",age<=25,age>=35
+                ann,  X   ,
+                bob,      ,  X
+                cat,      ,"#,
+        )
+        .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "attribute 'colour' has non-numeric value 'red' for an ordered scale"
+    )]
+    fn scale_ordinal_text() {
+        people()
+            .scale(&[("colour".to_string(), Scale::Ordinal(vec![1.0]))])
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown attribute 'weight'")]
+    fn scale_unknown_attribute() {
+        people()
+            .scale(&[("weight".to_string(), Scale::Nominal)])
+            .unwrap();
+    }
+
+    #[test]
+    fn scale_duplicate_attributes() {
+        let context = people();
+
+        for scales in [
+            vec![("age".to_string(), Scale::Ordinal(vec![30.0, 30.0]))],
+            vec![
+                ("colour".to_string(), Scale::Nominal),
+                ("colour".to_string(), Scale::Nominal),
+            ],
+        ] {
+            let error = context.scale(&scales).unwrap_err();
+
+            assert!(error.to_string().contains("more than once"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn value_non_finite() {
+        assert_eq!(Value::from("NaN"), Value::Text("NaN".to_string()));
+        assert_eq!(Value::from("inf"), Value::Text("inf".to_string()));
+        assert_eq!(Value::from("1e3"), Value::Number(1000.0));
+
+        let context = ManyValuedContext::from_csv(",size\na,NaN\nb,NaN").unwrap();
+        let actual = context
+            .scale(&[("size".to_string(), Scale::Nominal)])
+            .unwrap();
+
+        assert_eq!(actual.attributes().to_vec(), vec!["size=NaN".to_string()]);
+    }
+
+    #[test]
+    fn scale_nominal_text() {
+        let context =
+            ManyValuedContext::from_csv_reader(",code\na,01\nb,1\nc,01".as_bytes()).unwrap();
+        let actual = context
+            .scale(&[("code".to_string(), Scale::Nominal)])
+            .unwrap();
+        let expected = Context::from_csv(
+            r#",code=01,code=1
+                a,X,
+                b,,X
+                c,X,"#,
+        )
+        .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let error = ManyValuedContext::from_csv(",age,age\nann,1,2").unwrap_err();
+        assert!(error.to_string().contains("repeated"), "{}", error);
+
+        let error = ManyValuedContext::from_csv(",age\nann,1\nann,2").unwrap_err();
+        assert!(error.to_string().contains("already exists"), "{}", error);
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::bitset::BitSet;
+
+/// Interned object or attribute names, giving each name a stable index
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(crate) struct Names {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl Names {
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn as_slice(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Append a name. A repeated name keeps resolving to its first index.
+    pub fn push(&mut self, name: String) {
+        self.indices.entry(name.clone()).or_insert(self.names.len());
+        self.names.push(name);
+    }
+
+    pub fn rename(&mut self, index: usize, name: String) {
+        let old = std::mem::replace(&mut self.names[index], name.clone());
+
+        // A repeated old name now resolves to its next occurrence
+        if self.indices.get(&old) == Some(&index) {
+            match self.names.iter().position(|n| *n == old) {
+                Some(next) => self.indices.insert(old, next),
+                None => self.indices.remove(&old),
+            };
+        }
+
+        let first = self.indices.entry(name).or_insert(index);
+        *first = (*first).min(index);
+    }
+
+    /// Remove the name at `index`, moving the names after it down by one
+    pub fn remove(&mut self, index: usize) {
+        self.names.remove(index);
+        *self = std::mem::take(&mut self.names).into_iter().collect();
+    }
+
+    /// Convert names into an index set of every position holding one of them, skipping
+    /// unknown names. Returns `None` when none of a non-empty input is known.
+    pub fn set(&self, names: &[String]) -> Option<BitSet> {
+        let wanted: HashSet<&str> = names.iter().map(String::as_str).collect();
+        let mut set = BitSet::new(self.len());
+        let mut known = names.is_empty();
+
+        for (index, name) in self.names.iter().enumerate() {
+            if wanted.contains(name.as_str()) {
+                set.insert(index);
+                known = true;
+            }
+        }
+
+        known.then_some(set)
+    }
+
+    pub fn names(&self, set: &BitSet) -> Vec<String> {
+        set.ones().map(|i| self.names[i].to_string()).collect()
+    }
+}
+
+impl FromIterator<String> for Names {
+    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
+        let mut names = Self::default();
+
+        for name in iter {
+            names.push(name);
+        }
+
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Names;
+
+    #[test]
+    fn set() {
+        let names: Names = ["a", "b", "c"].iter().map(|n| n.to_string()).collect();
+
+        let set = names.set(&["c".to_string(), "a".to_string()]).unwrap();
+
+        assert_eq!(set.ones().collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(names.names(&set), vec!["a", "c"]);
+        assert_eq!(names.set(&[]).unwrap().ones().count(), 0);
+        assert_eq!(names.set(&["missing".to_string()]), None);
+    }
+
+    #[test]
+    fn push_duplicate() {
+        let names: Names = ["a", "b", "a"].iter().map(|n| n.to_string()).collect();
+
+        assert_eq!(names.len(), 3);
+        assert_eq!(names.index("a"), Some(0));
+        assert_eq!(names.index("b"), Some(1));
+        assert_eq!(
+            names
+                .set(&["a".to_string()])
+                .unwrap()
+                .ones()
+                .collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+    }
+
+    #[test]
+    fn remove() {
+        let mut names: Names = ["a", "b", "c", "b"].iter().map(|n| n.to_string()).collect();
+
+        names.remove(1);
+
+        assert_eq!(names.as_slice(), ["a", "c", "b"]);
+        assert_eq!(names.index("b"), Some(2));
+        assert_eq!(names.index("c"), Some(1));
+    }
+
+    #[test]
+    fn rename_duplicate() {
+        let mut names: Names = ["a", "b", "a"].iter().map(|n| n.to_string()).collect();
+
+        names.rename(0, "c".to_string());
+
+        assert_eq!(names.index("a"), Some(2));
+        assert_eq!(names.index("c"), Some(0));
+
+        names.rename(2, "d".to_string());
+
+        assert_eq!(names.index("a"), None);
+        assert_eq!(names.as_slice(), ["c", "b", "d"]);
+    }
+}